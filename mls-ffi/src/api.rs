@@ -8,6 +8,36 @@ use crate::error::MLSError;
 use crate::mls_context::MLSContextInner;
 use crate::types::*;
 
+// OpenMLS doesn't expose a single enum we can exhaustively pattern-match on
+// across `ProcessMessageError`'s nested validation/commit-staging error
+// trees, so we classify via the Debug text of the leaf error. This is a
+// pragmatic middle ground until openmls gives callers a structured reason
+// code; it still lets hosts branch on wrong-epoch vs. auth vs. generic
+// failures instead of string-matching the top-level error's Display message.
+fn classify_process_message_error(e: &impl std::fmt::Debug) -> MLSError {
+    let debug = format!("{:?}", e);
+    if debug.contains("WrongEpoch") {
+        MLSError::WrongEpoch
+    } else if debug.contains("UnknownMember") || debug.contains("Credential") {
+        MLSError::UnknownCredential
+    } else if debug.contains("Storage") {
+        MLSError::storage_failure(debug)
+    } else if debug.contains("InvalidCommit") || debug.contains("StageCommit") {
+        MLSError::InvalidCommit
+    } else {
+        MLSError::DecryptionFailed
+    }
+}
+
+fn classify_merge_commit_error(e: &impl std::fmt::Debug) -> MLSError {
+    let debug = format!("{:?}", e);
+    if debug.contains("Storage") {
+        MLSError::storage_failure(debug)
+    } else {
+        MLSError::MergeFailed
+    }
+}
+
 #[derive(uniffi::Object)]
 pub struct MLSContext {
     inner: Arc<RwLock<MLSContextInner>>,
@@ -23,6 +53,7 @@ impl MLSContext {
     }
 
     pub fn create_group(&self, identity_bytes: Vec<u8>, config: Option<GroupConfig>) -> Result<GroupCreationResult, MLSError> {
+        (|| -> Result<GroupCreationResult, MLSError> {
         eprintln!("[MLS-FFI] create_group: Starting");
         eprintln!("[MLS-FFI] Identity bytes: {} bytes", identity_bytes.len());
         
@@ -49,6 +80,7 @@ impl MLSContext {
         Ok(GroupCreationResult {
             group_id: group_id.to_vec(),
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn add_members(
@@ -56,6 +88,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         key_packages: Vec<KeyPackageData>,
     ) -> Result<AddMembersResult, MLSError> {
+        (|| -> Result<AddMembersResult, MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
         
@@ -140,6 +173,7 @@ impl MLSContext {
             commit_data,
             welcome_data,
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn encrypt_message(
@@ -147,6 +181,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         plaintext: Vec<u8>,
     ) -> Result<EncryptResult, MLSError> {
+        (|| -> Result<EncryptResult, MLSError> {
         eprintln!("[MLS-FFI] encrypt_message: Starting");
         eprintln!("[MLS-FFI] Group ID: {} ({} bytes)", hex::encode(&group_id), group_id.len());
         eprintln!("[MLS-FFI] Plaintext size: {} bytes", plaintext.len());
@@ -183,6 +218,7 @@ impl MLSContext {
 
         eprintln!("[MLS-FFI] encrypt_message: Completed successfully, ciphertext size: {} bytes", ciphertext.len());
         Ok(EncryptResult { ciphertext })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn decrypt_message(
@@ -190,6 +226,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         ciphertext: Vec<u8>,
     ) -> Result<DecryptResult, MLSError> {
+        (|| -> Result<DecryptResult, MLSError> {
         eprintln!("[MLS-FFI] decrypt_message: Starting decryption");
         eprintln!("[MLS-FFI] Group ID: {} ({} bytes)", hex::encode(&group_id), group_id.len());
         eprintln!("[MLS-FFI] Ciphertext size: {} bytes", ciphertext.len());
@@ -232,10 +269,10 @@ impl MLSContext {
                 .map_err(|e| {
                     eprintln!("[MLS-FFI] ERROR: OpenMLS process_message failed: {:?}", e);
                     eprintln!("[MLS-FFI] ERROR: Error type: {}", std::any::type_name_of_val(&e));
-                    MLSError::DecryptionFailed
+                    classify_process_message_error(&e)
                 })?;
             eprintln!("[MLS-FFI] OpenMLS process_message succeeded");
-            
+
             eprintln!("[MLS-FFI] Processing message content...");
             match processed.into_content() {
                 ProcessedMessageContent::ApplicationMessage(app_msg) => {
@@ -262,6 +299,7 @@ impl MLSContext {
 
         eprintln!("[MLS-FFI] decrypt_message: Completed successfully, plaintext size: {} bytes", plaintext.len());
         Ok(DecryptResult { plaintext })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn process_message(
@@ -269,6 +307,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         message_data: Vec<u8>,
     ) -> Result<ProcessedContent, MLSError> {
+        (|| -> Result<ProcessedContent, MLSError> {
         eprintln!("[MLS-FFI] process_message: Starting");
         eprintln!("[MLS-FFI] Group ID: {} ({} bytes)", hex::encode(&group_id), group_id.len());
         eprintln!("[MLS-FFI] Message data size: {} bytes", message_data.len());
@@ -315,7 +354,7 @@ impl MLSContext {
                     eprintln!("[MLS-FFI] ERROR: Error details: {:?}", e);
                     eprintln!("[MLS-FFI] ERROR: Error type: {}", std::any::type_name_of_val(&e));
                     eprintln!("[MLS-FFI] ERROR: Current epoch: {:?}", group.epoch());
-                    MLSError::DecryptionFailed
+                    classify_process_message_error(&e)
                 })?;
             eprintln!("[MLS-FFI] OpenMLS process_message succeeded!");
 
@@ -428,12 +467,14 @@ impl MLSContext {
                 },
             }
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn create_key_package(
         &self,
         identity_bytes: Vec<u8>,
     ) -> Result<KeyPackageResult, MLSError> {
+        (|| -> Result<KeyPackageResult, MLSError> {
         let inner = self.inner.read()
             .map_err(|_| MLSError::ContextNotInitialized)?;
         
@@ -477,6 +518,7 @@ impl MLSContext {
             .to_vec();
 
         Ok(KeyPackageResult { key_package_data, hash_ref })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn process_welcome(
@@ -485,6 +527,7 @@ impl MLSContext {
         identity_bytes: Vec<u8>,
         config: Option<GroupConfig>,
     ) -> Result<WelcomeResult, MLSError> {
+        (|| -> Result<WelcomeResult, MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -526,6 +569,7 @@ impl MLSContext {
         inner.add_group(group, &identity)?;
 
         Ok(WelcomeResult { group_id })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn export_secret(
@@ -535,6 +579,7 @@ impl MLSContext {
         context: Vec<u8>,
         key_length: u64,
     ) -> Result<ExportedSecret, MLSError> {
+        (|| -> Result<ExportedSecret, MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
         
@@ -547,9 +592,11 @@ impl MLSContext {
         })?;
         
         Ok(ExportedSecret { secret: secret.to_vec() })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn get_epoch(&self, group_id: Vec<u8>) -> Result<u64, MLSError> {
+        (|| -> Result<u64, MLSError> {
         eprintln!("[MLS-FFI] get_epoch: Starting");
         eprintln!("[MLS-FFI] Group ID: {}", hex::encode(&group_id));
         
@@ -566,6 +613,7 @@ impl MLSContext {
             eprintln!("[MLS-FFI] Current epoch: {}", epoch);
             Ok(epoch)
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     pub fn process_commit(
@@ -573,6 +621,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         commit_data: Vec<u8>,
     ) -> Result<ProcessCommitResult, MLSError> {
+        (|| -> Result<ProcessCommitResult, MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -646,12 +695,14 @@ impl MLSContext {
             new_epoch,
             update_proposals
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Clear pending commit for a group
     /// This should be called when a commit is rejected by the delivery service
     /// to clean up pending state in OpenMLS
     pub fn clear_pending_commit(&self, group_id: Vec<u8>) -> Result<(), MLSError> {
+        (|| -> Result<(), MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -662,6 +713,7 @@ impl MLSContext {
                 .map_err(|_| MLSError::OpenMLSError)?;
             Ok(())
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Store a proposal in the proposal queue after validation
@@ -671,6 +723,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         proposal_ref: ProposalRef,
     ) -> Result<(), MLSError> {
+        (|| -> Result<(), MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -683,6 +736,7 @@ impl MLSContext {
             // Application can maintain its own list of approved proposals
             Ok(())
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// List all pending proposals for a group
@@ -690,6 +744,7 @@ impl MLSContext {
         &self,
         group_id: Vec<u8>,
     ) -> Result<Vec<ProposalRef>, MLSError> {
+        (|| -> Result<Vec<ProposalRef>, MLSError> {
         let inner = self.inner.read()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -718,6 +773,7 @@ impl MLSContext {
 
             Ok(proposal_refs)
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Remove a proposal from the proposal queue
@@ -726,6 +782,7 @@ impl MLSContext {
         group_id: Vec<u8>,
         proposal_ref: ProposalRef,
     ) -> Result<(), MLSError> {
+        (|| -> Result<(), MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -739,6 +796,7 @@ impl MLSContext {
                 .map_err(|_| MLSError::OpenMLSError)?;
             Ok(())
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Commit all pending proposals that have been validated and stored
@@ -746,6 +804,7 @@ impl MLSContext {
         &self,
         group_id: Vec<u8>,
     ) -> Result<Vec<u8>, MLSError> {
+        (|| -> Result<Vec<u8>, MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -768,11 +827,13 @@ impl MLSContext {
 
             Ok(commit_data)
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Merge a pending commit after validation
     /// This should be called after the commit has been accepted by the delivery service
     pub fn merge_pending_commit(&self, group_id: Vec<u8>) -> Result<u64, MLSError> {
+        (|| -> Result<u64, MLSError> {
         let mut inner = self.inner.write()
             .map_err(|_| MLSError::ContextNotInitialized)?;
 
@@ -780,18 +841,21 @@ impl MLSContext {
 
         inner.with_group(&gid, |group, provider, _signer| {
             group.merge_pending_commit(provider)
-                .map_err(|_| MLSError::MergeFailed)?;
+                .map_err(|e| classify_merge_commit_error(&e))?;
 
             let new_epoch = group.epoch().as_u64();
             Ok(new_epoch)
         })
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Merge a staged commit after validation
     /// This should be called after validating incoming commits from other members
     pub fn merge_staged_commit(&self, group_id: Vec<u8>) -> Result<u64, MLSError> {
+        (|| -> Result<u64, MLSError> {
         // OpenMLS uses the same internal method for both pending and staged commits
         self.merge_pending_commit(group_id)
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Check if a group exists in local storage
@@ -816,6 +880,7 @@ impl MLSContext {
     /// - Returns: Serialized group state bytes
     /// - Throws: MLSError if group not found or serialization fails
     pub fn export_group_state(&self, group_id: Vec<u8>) -> Result<Vec<u8>, MLSError> {
+        (|| -> Result<Vec<u8>, MLSError> {
         eprintln!("[MLS-FFI] export_group_state: Starting");
 
         let inner = self.inner.read()
@@ -825,6 +890,7 @@ impl MLSContext {
 
         eprintln!("[MLS-FFI] export_group_state: Complete, {} bytes", state_bytes.len());
         Ok(state_bytes)
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Import a group's state from persistent storage
@@ -837,6 +903,7 @@ impl MLSContext {
     /// - Returns: Group ID of the imported group
     /// - Throws: MLSError if deserialization fails
     pub fn import_group_state(&self, state_bytes: Vec<u8>) -> Result<Vec<u8>, MLSError> {
+        (|| -> Result<Vec<u8>, MLSError> {
         eprintln!("[MLS-FFI] import_group_state: Starting with {} bytes", state_bytes.len());
 
         let mut inner = self.inner.write()
@@ -846,6 +913,7 @@ impl MLSContext {
 
         eprintln!("[MLS-FFI] import_group_state: Complete, group ID: {}", hex::encode(&group_id));
         Ok(group_id)
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Serialize the entire MLS storage for persistence
@@ -857,6 +925,7 @@ impl MLSContext {
     /// - Returns: Serialized storage bytes
     /// - Throws: MLSError if serialization fails
     pub fn serialize_storage(&self) -> Result<Vec<u8>, MLSError> {
+        (|| -> Result<Vec<u8>, MLSError> {
         eprintln!("[MLS-FFI] serialize_storage: Starting");
 
         let inner = self.inner.read()
@@ -866,6 +935,7 @@ impl MLSContext {
 
         eprintln!("[MLS-FFI] serialize_storage: Complete, {} bytes", storage_bytes.len());
         Ok(storage_bytes)
+            })().map_err(MLSError::record_as_last_error)
     }
 
     /// Deserialize and restore MLS storage from persistent bytes
@@ -880,6 +950,7 @@ impl MLSContext {
     ///   - storage_bytes: Serialized storage from serialize_storage
     /// - Throws: MLSError if deserialization fails
     pub fn deserialize_storage(&self, storage_bytes: Vec<u8>) -> Result<(), MLSError> {
+        (|| -> Result<(), MLSError> {
         eprintln!("[MLS-FFI] deserialize_storage: Starting with {} bytes", storage_bytes.len());
 
         let mut inner = self.inner.write()
@@ -889,5 +960,6 @@ impl MLSContext {
 
         eprintln!("[MLS-FFI] deserialize_storage: Complete");
         Ok(())
+            })().map_err(MLSError::record_as_last_error)
     }
 }
@@ -1,3 +1,4 @@
+use std::cell::RefCell;
 use thiserror::Error;
 
 #[derive(Error, Debug, uniffi::Error)]
@@ -5,43 +6,43 @@ use thiserror::Error;
 pub enum MLSError {
     #[error("Invalid input: {message}")]
     InvalidInput { message: String },
-    
+
     #[error("Group not found: {message}")]
     GroupNotFound { message: String },
-    
+
     #[error("Invalid key package")]
     InvalidKeyPackage,
-    
+
     #[error("Failed to add members")]
     AddMembersFailed,
-    
+
     #[error("Encryption failed")]
     EncryptionFailed,
-    
+
     #[error("Decryption failed")]
     DecryptionFailed,
-    
+
     #[error("Serialization error")]
     SerializationError,
-    
+
     #[error("OpenMLS error")]
     OpenMLSError,
-    
+
     #[error("Invalid group ID")]
     InvalidGroupId,
-    
+
     #[error("Secret export failed")]
     SecretExportFailed,
-    
+
     #[error("Commit processing failed")]
     CommitProcessingFailed,
-    
+
     #[error("Invalid commit")]
     InvalidCommit,
-    
+
     #[error("Invalid data")]
     InvalidData,
-    
+
     #[error("Context not initialized")]
     ContextNotInitialized,
 
@@ -50,6 +51,15 @@ pub enum MLSError {
 
     #[error("Merge failed")]
     MergeFailed,
+
+    #[error("Message was encrypted for a different epoch than the local group state")]
+    WrongEpoch,
+
+    #[error("Message references an unknown or unverifiable credential")]
+    UnknownCredential,
+
+    #[error("Key store or storage backend failure: {message}")]
+    StorageFailure { message: String },
 }
 
 impl MLSError {
@@ -64,4 +74,104 @@ impl MLSError {
     pub fn wire_format_policy_violation(msg: impl Into<String>) -> Self {
         Self::WireFormatPolicyViolation { message: msg.into() }
     }
+
+    pub fn storage_failure(msg: impl Into<String>) -> Self {
+        Self::StorageFailure { message: msg.into() }
+    }
+
+    /// Stable category for this error, meant to be branched on by FFI hosts
+    /// instead of matching the (translatable, free-form) display message.
+    pub fn code(&self) -> MlsErrorCode {
+        match self {
+            Self::InvalidInput { .. } => MlsErrorCode::InvalidInput,
+            Self::GroupNotFound { .. } => MlsErrorCode::GroupNotFound,
+            Self::InvalidKeyPackage => MlsErrorCode::InvalidKeyPackage,
+            Self::AddMembersFailed => MlsErrorCode::AddMembersFailed,
+            Self::EncryptionFailed => MlsErrorCode::EncryptionFailed,
+            Self::DecryptionFailed => MlsErrorCode::DecryptionFailed,
+            Self::SerializationError => MlsErrorCode::SerializationError,
+            Self::OpenMLSError => MlsErrorCode::OpenMlsError,
+            Self::InvalidGroupId => MlsErrorCode::InvalidGroupId,
+            Self::SecretExportFailed => MlsErrorCode::SecretExportFailed,
+            Self::CommitProcessingFailed => MlsErrorCode::CommitProcessingFailed,
+            Self::InvalidCommit => MlsErrorCode::InvalidCommit,
+            Self::InvalidData => MlsErrorCode::InvalidData,
+            Self::ContextNotInitialized => MlsErrorCode::ContextNotInitialized,
+            Self::WireFormatPolicyViolation { .. } => MlsErrorCode::WireFormatPolicyViolation,
+            Self::MergeFailed => MlsErrorCode::MergeFailed,
+            Self::WrongEpoch => MlsErrorCode::WrongEpoch,
+            Self::UnknownCredential => MlsErrorCode::UnknownCredential,
+            Self::StorageFailure { .. } => MlsErrorCode::StorageFailure,
+        }
+    }
+}
+
+/// Stable error categories exposed to FFI hosts so they can branch on the
+/// *kind* of failure (e.g. retry-on-wrong-epoch, fail-fast-on-auth) without
+/// string-matching `MLSError`'s display message, which is free-form and may
+/// change wording between releases.
+#[derive(uniffi::Enum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MlsErrorCode {
+    InvalidInput,
+    GroupNotFound,
+    InvalidKeyPackage,
+    AddMembersFailed,
+    EncryptionFailed,
+    DecryptionFailed,
+    SerializationError,
+    OpenMlsError,
+    InvalidGroupId,
+    SecretExportFailed,
+    CommitProcessingFailed,
+    InvalidCommit,
+    InvalidData,
+    ContextNotInitialized,
+    WireFormatPolicyViolation,
+    MergeFailed,
+    WrongEpoch,
+    UnknownCredential,
+    StorageFailure,
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<(MlsErrorCode, String)>> = RefCell::new(None);
+}
+
+impl MLSError {
+    /// Record this error as the thread's "last error" and hand it back
+    /// unchanged. Call this at the point an error actually crosses the
+    /// uniffi boundary (the tail of each `#[uniffi::export]` method), not on
+    /// every `MLSError` that transiently exists inside one - most of those
+    /// are classified, matched on, and discarded well before the function
+    /// returns, and would otherwise clobber the slot with an error the host
+    /// never saw. This lets hosts that can't easily thread a typed error
+    /// through (logging shims, C callers) recover the code and message after
+    /// the fact via `mls_last_error`.
+    pub(crate) fn record_as_last_error(self) -> Self {
+        LAST_ERROR.with(|slot| {
+            *slot.borrow_mut() = Some((self.code(), self.to_string()));
+        });
+        self
+    }
+}
+
+#[derive(uniffi::Record)]
+pub struct LastError {
+    pub code: MlsErrorCode,
+    pub message: String,
+}
+
+#[uniffi::export]
+pub fn mls_error_code(error: &MLSError) -> MlsErrorCode {
+    error.code()
+}
+
+#[uniffi::export]
+pub fn mls_last_error() -> Option<LastError> {
+    LAST_ERROR.with(|slot| {
+        slot.borrow().as_ref().map(|(code, message)| LastError {
+            code: *code,
+            message: message.clone(),
+        })
+    })
 }
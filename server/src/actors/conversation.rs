@@ -2,11 +2,17 @@ use async_trait::async_trait;
 use ractor::{Actor, ActorProcessingErr, ActorRef};
 use sqlx::PgPool;
 use std::{collections::HashMap, sync::Arc};
+use tokio::sync::broadcast;
 use tracing::{debug, info};
 
-use super::messages::{ConvoMessage, KeyPackageHashEntry};
+use super::messages::{ConvoEvent, ConvoMessage, KeyPackageHashEntry};
 use crate::realtime::{SseState, StreamEvent};
 
+/// Capacity of each conversation's ephemeral event broadcast channel. Sized
+/// for a handful of concurrent long-poll subscribers; lagging subscribers
+/// simply miss older events rather than blocking publishers.
+const EVENT_CHANNEL_CAPACITY: usize = 256;
+
 /// Manages state for a single conversation, ensuring sequential processing
 /// of all epoch-modifying operations to prevent race conditions.
 ///
@@ -84,12 +90,15 @@ impl Actor for ConversationActor {
             args.convo_id, current_epoch
         );
 
+        let (event_tx, _) = broadcast::channel(EVENT_CHANNEL_CAPACITY);
+
         Ok(ConversationActorState {
             convo_id: args.convo_id,
             current_epoch: current_epoch as u32,
             unread_counts: HashMap::new(),
             db_pool: args.db_pool,
             sse_state: args.sse_state,
+            event_tx,
         })
     }
 
@@ -155,6 +164,14 @@ impl Actor for ConversationActor {
                 info!("ConversationActor shutting down");
                 // Could persist state here if needed
             }
+            ConvoMessage::Subscribe { reply } => {
+                let _ = reply.send(state.event_tx.subscribe());
+            }
+            ConvoMessage::Notify(event) => {
+                // No subscribers is the common case (no one long-polling
+                // this conversation right now) - not an error.
+                let _ = state.event_tx.send(event);
+            }
         }
         Ok(())
     }
@@ -173,6 +190,8 @@ impl Actor for ConversationActor {
 /// - `unread_counts`: In-memory cache of unread counts per member (periodically synced to DB)
 /// - `db_pool`: PostgreSQL connection pool for database operations
 /// - `sse_state`: SSE state for real-time event broadcasting
+/// - `event_tx`: Broadcast channel for ephemeral events delivered to
+///   `subscribeConvo` long-poll waiters (see [`ConvoMessage::Subscribe`])
 ///
 /// # Concurrency Model
 ///
@@ -184,6 +203,7 @@ pub struct ConversationActorState {
     unread_counts: HashMap<String, u32>, // member_did -> count
     db_pool: PgPool,
     sse_state: Arc<SseState>,
+    event_tx: broadcast::Sender<ConvoEvent>,
 }
 
 impl ConversationActorState {
@@ -891,6 +911,12 @@ impl ConversationActorState {
 
         debug!("Message stored with sequence number {}", seq);
 
+        // Wake any subscribeConvo long-pollers waiting on this conversation
+        let _ = self.event_tx.send(ConvoEvent::Message {
+            seq,
+            message_id: row_id.clone(),
+        });
+
         // Update unread counts for all members except sender's devices in database
         // In multi-device mode, user_did is the base DID, so this excludes all sender's devices
         sqlx::query(
@@ -1,5 +1,6 @@
 use anyhow::Result;
-use tokio::sync::oneshot;
+use serde::Serialize;
+use tokio::sync::{broadcast, oneshot};
 
 /// Messages that can be sent to a [`ConversationActor`].
 ///
@@ -143,6 +144,55 @@ pub enum ConvoMessage {
     /// The actor will complete any in-flight operations before stopping.
     /// This is a fire-and-forget message.
     Shutdown,
+
+    /// Registers a long-poll waiter for ephemeral conversation events.
+    ///
+    /// Used by the `subscribeConvo` IMAP-IDLE-style endpoint: the caller
+    /// receives a [`broadcast::Receiver`] and awaits it with its own
+    /// timeout, rather than blocking the actor's mailbox while it waits.
+    ///
+    /// # Fields
+    ///
+    /// - `reply`: Channel to receive a receiver subscribed to this
+    ///   conversation's [`ConvoEvent`] broadcast stream
+    Subscribe {
+        reply: oneshot::Sender<broadcast::Receiver<ConvoEvent>>,
+    },
+
+    /// Publishes an ephemeral event (new message, reaction, typing) to any
+    /// actors currently long-polling via [`ConvoMessage::Subscribe`].
+    ///
+    /// This is a fire-and-forget operation. If no one is subscribed, the
+    /// event is simply dropped - callers don't need to check for waiters
+    /// before notifying.
+    ///
+    /// # Fields
+    ///
+    /// - `0`: The event to publish
+    Notify(ConvoEvent),
+}
+
+/// An ephemeral, in-process event surfaced to long-poll subscribers of a
+/// conversation via [`ConvoMessage::Subscribe`]/[`ConvoMessage::Notify`].
+///
+/// Unlike [`ConvoMessage`] itself, these are broadcast (fan-out to every
+/// subscriber) rather than request-reply, and are not persisted - a
+/// subscriber that misses one because it wasn't listening yet should fall
+/// back to `getMessages`/`lastSeq` rather than expect replay.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ConvoEvent {
+    /// A new message (application or commit) was stored.
+    Message { seq: i64, message_id: String },
+    /// A reaction was added to or removed from a message.
+    Reaction {
+        message_id: String,
+        did: String,
+        reaction: String,
+        action: String,
+    },
+    /// A member started or stopped typing.
+    Typing { did: String, is_typing: bool },
 }
 
 /// Associates a DID with its corresponding key package hash.
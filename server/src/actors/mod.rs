@@ -4,7 +4,7 @@ mod registry;
 mod supervisor;
 
 pub use conversation::{ConversationActor, ConvoActorArgs};
-pub use messages::{ConvoMessage, KeyPackageHashEntry};
+pub use messages::{ConvoEvent, ConvoMessage, KeyPackageHashEntry};
 pub use registry::ActorRegistry;
 
 #[cfg(test)]
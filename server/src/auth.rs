@@ -156,6 +156,31 @@ struct CachedDidDoc {
     cached_at: DateTime<Utc>,
 }
 
+/// Multicodec prefixes for the key types AT Protocol signs with, as used in
+/// `publicKeyMultibase` (base58btc, `z`-prefixed per the multibase spec).
+/// See https://atproto.com/specs/cryptography.
+const MULTICODEC_P256: [u8; 2] = [0x80, 0x24];
+const MULTICODEC_SECP256K1: [u8; 2] = [0xe7, 0x01];
+
+/// Decode a `did:key`-style `publicKeyMultibase` value into its raw SEC1
+/// (compressed) point bytes, verifying the multicodec prefix matches
+/// `expected`.
+fn decode_multikey(multibase: &str, expected: &[u8; 2]) -> Result<Vec<u8>, AuthError> {
+    let encoded = multibase
+        .strip_prefix('z')
+        .ok_or_else(|| AuthError::UnsupportedKeyType("publicKeyMultibase must be base58btc ('z'-prefixed)".into()))?;
+    let bytes = bs58::decode(encoded)
+        .into_vec()
+        .map_err(|e| AuthError::InvalidToken(format!("bad publicKeyMultibase: {}", e)))?;
+    if bytes.len() < 2 || &bytes[0..2] != expected {
+        return Err(AuthError::UnsupportedKeyType(format!(
+            "publicKeyMultibase has unexpected multicodec prefix {:02x?}",
+            bytes.get(0..2).unwrap_or_default()
+        )));
+    }
+    Ok(bytes[2..].to_vec())
+}
+
 /// Authenticated user extracted from request
 #[derive(Debug, Clone)]
 pub struct AuthUser {
@@ -167,6 +192,11 @@ pub struct AuthUser {
 #[derive(Clone)]
 pub struct AuthMiddleware {
     did_cache: Cache<String, CachedDidDoc>,
+    /// Negative cache for DID resolution failures, so a peer that's
+    /// temporarily unresolvable (down PLC directory, misconfigured
+    /// did:web) doesn't cause a fresh outbound resolution attempt on every
+    /// single inbound request while it stays broken.
+    did_failure_cache: Cache<String, String>,
     rate_limiters: Arc<RwLock<HashMap<String, Arc<RateLimiter<NotKeyed, InMemoryState, DefaultClock>>>>>,
     http_client: reqwest::Client,
     cache_ttl_seconds: u64,
@@ -188,11 +218,22 @@ impl AuthMiddleware {
             .time_to_live(std::time::Duration::from_secs(cache_ttl_seconds))
             .build();
 
+        let did_failure_cache = Cache::builder()
+            .max_capacity(10_000)
+            .time_to_live(std::time::Duration::from_secs(
+                std::env::var("DID_NEGATIVE_CACHE_TTL_SECONDS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .unwrap_or(30),
+            ))
+            .build();
+
         let quota = Quota::per_second(NonZeroU32::new(rate_limit_requests.max(1)).unwrap())
             .allow_burst(NonZeroU32::new((rate_limit_requests.max(1) / 10).max(1)).unwrap());
 
         Self {
             did_cache,
+            did_failure_cache,
             rate_limiters: Arc::new(RwLock::new(HashMap::new())),
             http_client: reqwest::Client::builder()
                 .timeout(std::time::Duration::from_secs(10))
@@ -252,13 +293,19 @@ async fn verify_jwt(&self, token: &str) -> Result<AtProtoClaims, AuthError> {
             use p256::EncodedPoint;
             let did_doc = self.resolve_did(&claims.iss).await?;
             let vm = did_doc.verification_method.first().ok_or(AuthError::MissingVerificationMethod)?;
-            let jwk = vm.public_key_jwk.as_ref().ok_or(AuthError::MissingVerificationMethod)?;
-            if jwk.kty != "EC" || jwk.crv.to_ascii_uppercase() != "P-256" { return Err(AuthError::UnsupportedKeyType(format!("Expected EC P-256, got {} {}", jwk.kty, jwk.crv))); }
-            let x = URL_SAFE_NO_PAD.decode(&jwk.x).map_err(|e| AuthError::InvalidToken(format!("bad jwk.x: {}", e)))?;
-            let y = URL_SAFE_NO_PAD.decode(jwk.y.as_ref().ok_or_else(|| AuthError::MissingVerificationMethod)?)
-                .map_err(|e| AuthError::InvalidToken(format!("bad jwk.y: {}", e)))?;
-            let ep = EncodedPoint::from_affine_coordinates(p256::FieldBytes::from_slice(&x), p256::FieldBytes::from_slice(&y), false);
-            let vk = VerifyingKey::from_encoded_point(&ep).map_err(|_| AuthError::InvalidToken("invalid P-256 point".into()))?;
+            let vk = if let Some(jwk) = vm.public_key_jwk.as_ref() {
+                if jwk.kty != "EC" || jwk.crv.to_ascii_uppercase() != "P-256" { return Err(AuthError::UnsupportedKeyType(format!("Expected EC P-256, got {} {}", jwk.kty, jwk.crv))); }
+                let x = URL_SAFE_NO_PAD.decode(&jwk.x).map_err(|e| AuthError::InvalidToken(format!("bad jwk.x: {}", e)))?;
+                let y = URL_SAFE_NO_PAD.decode(jwk.y.as_ref().ok_or_else(|| AuthError::MissingVerificationMethod)?)
+                    .map_err(|e| AuthError::InvalidToken(format!("bad jwk.y: {}", e)))?;
+                let ep = EncodedPoint::from_affine_coordinates(p256::FieldBytes::from_slice(&x), p256::FieldBytes::from_slice(&y), false);
+                VerifyingKey::from_encoded_point(&ep).map_err(|_| AuthError::InvalidToken("invalid P-256 point".into()))?
+            } else if let Some(multibase) = vm.public_key_multibase.as_ref() {
+                let raw = decode_multikey(multibase, &MULTICODEC_P256)?;
+                VerifyingKey::from_sec1_bytes(&raw).map_err(|_| AuthError::InvalidToken("invalid P-256 multikey".into()))?
+            } else {
+                return Err(AuthError::MissingVerificationMethod);
+            };
             let sig_bytes = URL_SAFE_NO_PAD.decode(parts[2]).map_err(|e| AuthError::InvalidToken(format!("Invalid b64 sig: {}", e)))?;
             let sig = Signature::from_slice(&sig_bytes).map_err(|_| AuthError::InvalidToken("invalid ES256 signature".into()))?;
             vk.verify(signing_input.as_bytes(), &sig).map_err(|_| AuthError::InvalidSignature)?;
@@ -270,15 +317,21 @@ async fn verify_jwt(&self, token: &str) -> Result<AtProtoClaims, AuthError> {
             use k256::EncodedPoint;
             let did_doc = self.resolve_did(&claims.iss).await?;
             let vm = did_doc.verification_method.first().ok_or(AuthError::MissingVerificationMethod)?;
-            let jwk = vm.public_key_jwk.as_ref().ok_or(AuthError::MissingVerificationMethod)?;
-            if jwk.kty != "EC" { return Err(AuthError::UnsupportedKeyType(format!("Expected EC, got {}", jwk.kty))); }
-            let crv = jwk.crv.to_ascii_lowercase();
-            if crv != "secp256k1" && crv != "k-256" && crv != "p-256k" { return Err(AuthError::UnsupportedKeyType(format!("Expected secp256k1, got {}", jwk.crv))); }
-            let x = URL_SAFE_NO_PAD.decode(&jwk.x).map_err(|e| AuthError::InvalidToken(format!("bad jwk.x: {}", e)))?;
-            let y = URL_SAFE_NO_PAD.decode(jwk.y.as_ref().ok_or_else(|| AuthError::MissingVerificationMethod)?)
-                .map_err(|e| AuthError::InvalidToken(format!("bad jwk.y: {}", e)))?;
-            let ep = EncodedPoint::from_affine_coordinates(p256::FieldBytes::from_slice(&x), p256::FieldBytes::from_slice(&y), false);
-            let vk = VerifyingKey::from_encoded_point(&ep).map_err(|_| AuthError::InvalidToken("invalid secp256k1 point".into()))?;
+            let vk = if let Some(jwk) = vm.public_key_jwk.as_ref() {
+                if jwk.kty != "EC" { return Err(AuthError::UnsupportedKeyType(format!("Expected EC, got {}", jwk.kty))); }
+                let crv = jwk.crv.to_ascii_lowercase();
+                if crv != "secp256k1" && crv != "k-256" && crv != "p-256k" { return Err(AuthError::UnsupportedKeyType(format!("Expected secp256k1, got {}", jwk.crv))); }
+                let x = URL_SAFE_NO_PAD.decode(&jwk.x).map_err(|e| AuthError::InvalidToken(format!("bad jwk.x: {}", e)))?;
+                let y = URL_SAFE_NO_PAD.decode(jwk.y.as_ref().ok_or_else(|| AuthError::MissingVerificationMethod)?)
+                    .map_err(|e| AuthError::InvalidToken(format!("bad jwk.y: {}", e)))?;
+                let ep = EncodedPoint::from_affine_coordinates(p256::FieldBytes::from_slice(&x), p256::FieldBytes::from_slice(&y), false);
+                VerifyingKey::from_encoded_point(&ep).map_err(|_| AuthError::InvalidToken("invalid secp256k1 point".into()))?
+            } else if let Some(multibase) = vm.public_key_multibase.as_ref() {
+                let raw = decode_multikey(multibase, &MULTICODEC_SECP256K1)?;
+                VerifyingKey::from_sec1_bytes(&raw).map_err(|_| AuthError::InvalidToken("invalid secp256k1 multikey".into()))?
+            } else {
+                return Err(AuthError::MissingVerificationMethod);
+            };
             let sig_bytes = URL_SAFE_NO_PAD.decode(parts[2]).map_err(|e| AuthError::InvalidToken(format!("Invalid b64 sig: {}", e)))?;
             let sig = Signature::from_slice(&sig_bytes).map_err(|_| AuthError::InvalidToken("invalid ES256K signature".into()))?;
             vk.verify(signing_input.as_bytes(), &sig).map_err(|_| AuthError::InvalidSignature)?;
@@ -301,15 +354,29 @@ async fn verify_jwt(&self, token: &str) -> Result<AtProtoClaims, AuthError> {
             return Ok(cached.doc);
         }
 
+        // Negative cache: don't retry a DID that failed to resolve recently
+        if let Some(reason) = self.did_failure_cache.get(did).await {
+            debug!("DID document negative-cache hit for {}", did);
+            return Err(AuthError::DidResolutionFailed(reason));
+        }
+
         debug!("Resolving DID document for {}", did);
 
         // Resolve based on DID method
-        let doc = if did.starts_with("did:plc:") {
-            self.resolve_plc_did(did).await?
+        let doc = match if did.starts_with("did:plc:") {
+            self.resolve_plc_did(did).await
         } else if did.starts_with("did:web:") {
-            self.resolve_web_did(did).await?
+            self.resolve_web_did(did).await
         } else {
             return Err(AuthError::InvalidDid(format!("Unsupported DID method: {}", did)));
+        } {
+            Ok(doc) => doc,
+            Err(e) => {
+                self.did_failure_cache
+                    .insert(did.to_string(), e.to_string())
+                    .await;
+                return Err(e);
+            }
         };
 
         // Cache the result
@@ -447,6 +514,48 @@ pub fn enforce_standard(claims: &AtProtoClaims, endpoint_nsid: &str) -> Result<(
     Ok(())
 }
 
+/// Signing mode for [`generate_jwt`].
+pub enum JwtSigningMode<'a> {
+    /// Dev/staging shared-secret signing (reads `JWT_SECRET`).
+    Hs256,
+    /// Inter-service signing with the issuer's own secp256k1 key (raw
+    /// 32-byte scalar), mirroring the `did:plc` signing key AT Protocol
+    /// accounts actually use.
+    Es256k(&'a [u8]),
+}
+
+/// Generate a signed JWT for the given claims. Exists mainly so tests can
+/// exercise [`AuthMiddleware::verify_jwt`]'s ES256K path without a live,
+/// PLC-resolvable signing key; also usable as a same-process HS256 dev
+/// token issuer.
+pub fn generate_jwt(claims: &AtProtoClaims, mode: JwtSigningMode<'_>) -> Result<String, AuthError> {
+    match mode {
+        JwtSigningMode::Hs256 => {
+            let secret = std::env::var("JWT_SECRET")
+                .map_err(|_| AuthError::InvalidToken("HS256 requires JWT_SECRET".into()))?;
+            let header = jsonwebtoken::Header::new(jsonwebtoken::Algorithm::HS256);
+            jsonwebtoken::encode(&header, claims, &jsonwebtoken::EncodingKey::from_secret(secret.as_bytes()))
+                .map_err(|e| AuthError::Internal(format!("HS256 sign failed: {}", e)))
+        }
+        JwtSigningMode::Es256k(secret_key_bytes) => {
+            use k256::ecdsa::{signature::Signer, Signature, SigningKey};
+            let signing_key = SigningKey::from_slice(secret_key_bytes)
+                .map_err(|e| AuthError::Internal(format!("invalid secp256k1 key: {}", e)))?;
+            let header = serde_json::json!({ "alg": "ES256K", "typ": "JWT" });
+            let header_b64 = URL_SAFE_NO_PAD.encode(
+                serde_json::to_vec(&header).map_err(|e| AuthError::Internal(e.to_string()))?,
+            );
+            let payload_b64 = URL_SAFE_NO_PAD.encode(
+                serde_json::to_vec(claims).map_err(|e| AuthError::Internal(e.to_string()))?,
+            );
+            let signing_input = format!("{}.{}", header_b64, payload_b64);
+            let sig: Signature = signing_key.sign(signing_input.as_bytes());
+            let sig_b64 = URL_SAFE_NO_PAD.encode(sig.to_bytes());
+            Ok(format!("{}.{}", signing_input, sig_b64))
+        }
+    }
+}
+
 /// Axum extractor for authenticated requests
 #[async_trait]
 impl<S> FromRequestParts<S> for AuthUser
@@ -489,4 +598,75 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+
+    fn sample_claims() -> AtProtoClaims {
+        AtProtoClaims {
+            iss: "did:plc:test123".to_string(),
+            aud: "did:web:ds.example.com".to_string(),
+            exp: Utc::now().timestamp() + 120,
+            iat: Some(Utc::now().timestamp()),
+            sub: None,
+            lxm: Some("blue.catbird.mls.ds.deliverMessage".to_string()),
+            jti: Some("test-jti".to_string()),
+        }
+    }
+
+    #[test]
+    fn generate_jwt_es256k_produces_three_parts_with_expected_header() {
+        use k256::ecdsa::SigningKey;
+        let signing_key = SigningKey::from_slice(&[0x11u8; 32]).unwrap();
+        let token = generate_jwt(
+            &sample_claims(),
+            JwtSigningMode::Es256k(&signing_key.to_bytes()),
+        )
+        .unwrap();
+
+        let parts: Vec<&str> = token.split('.').collect();
+        assert_eq!(parts.len(), 3);
+
+        let header_json = URL_SAFE_NO_PAD.decode(parts[0]).unwrap();
+        let header: serde_json::Value = serde_json::from_slice(&header_json).unwrap();
+        assert_eq!(header["alg"], "ES256K");
+
+        let payload_json = URL_SAFE_NO_PAD.decode(parts[1]).unwrap();
+        let claims: AtProtoClaims = serde_json::from_slice(&payload_json).unwrap();
+        assert_eq!(claims.iss, "did:plc:test123");
+    }
+
+    #[test]
+    fn generate_jwt_es256k_signature_verifies_against_the_public_key() {
+        use k256::ecdsa::{signature::Verifier, Signature, SigningKey};
+        let signing_key = SigningKey::from_slice(&[0x22u8; 32]).unwrap();
+        let verifying_key = signing_key.verifying_key();
+
+        let token = generate_jwt(
+            &sample_claims(),
+            JwtSigningMode::Es256k(&signing_key.to_bytes()),
+        )
+        .unwrap();
+
+        let parts: Vec<&str> = token.split('.').collect();
+        let signing_input = format!("{}.{}", parts[0], parts[1]);
+        let sig_bytes = URL_SAFE_NO_PAD.decode(parts[2]).unwrap();
+        let sig = Signature::from_slice(&sig_bytes).unwrap();
+
+        verifying_key
+            .verify(signing_input.as_bytes(), &sig)
+            .expect("signature should verify against the matching public key");
+    }
+
+    #[test]
+    fn decode_multikey_rejects_wrong_multicodec_prefix() {
+        // P-256 prefix, but asked to decode as secp256k1.
+        let encoded = bs58::encode([&MULTICODEC_P256[..], &[0u8; 33]].concat()).into_string();
+        let multibase = format!("z{}", encoded);
+        let err = decode_multikey(&multibase, &MULTICODEC_SECP256K1).unwrap_err();
+        assert!(matches!(err, AuthError::UnsupportedKeyType(_)));
+    }
+
+    #[test]
+    fn decode_multikey_rejects_missing_z_prefix() {
+        let err = decode_multikey("abcdef", &MULTICODEC_P256).unwrap_err();
+        assert!(matches!(err, AuthError::UnsupportedKeyType(_)));
+    }
 }
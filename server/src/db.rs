@@ -555,6 +555,111 @@ pub async fn list_messages_since_seq(
     Ok(messages)
 }
 
+/// List messages with seq < `before_seq`, newest-first, capped at `limit`.
+/// Used by the `BEFORE` CHATHISTORY-style selector in `getMessages`.
+pub async fn list_messages_before_seq(
+    pool: &DbPool,
+    convo_id: &str,
+    before_seq: i64,
+    limit: i64,
+) -> Result<Vec<Message>> {
+    let messages = sqlx::query_as::<_, Message>(
+        r#"
+        SELECT id, convo_id, sender_did, message_type, CAST(epoch AS BIGINT), CAST(seq AS BIGINT), ciphertext, created_at, expires_at
+        FROM messages
+        WHERE convo_id = $1 AND seq < $2 AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY epoch DESC, seq DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(convo_id)
+    .bind(before_seq)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list messages before sequence number")?;
+
+    Ok(messages)
+}
+
+/// List messages straddling `around_seq`: up to `limit / 2` messages with
+/// seq < `around_seq`, and the remainder with seq >= `around_seq`, merged
+/// and returned in ascending seq order. Used by the `AROUND` selector.
+pub async fn list_messages_around_seq(
+    pool: &DbPool,
+    convo_id: &str,
+    around_seq: i64,
+    limit: i64,
+) -> Result<Vec<Message>> {
+    let before_limit = limit / 2;
+    let after_limit = limit - before_limit;
+
+    let mut before = sqlx::query_as::<_, Message>(
+        r#"
+        SELECT id, convo_id, sender_did, message_type, CAST(epoch AS BIGINT), CAST(seq AS BIGINT), ciphertext, created_at, expires_at
+        FROM messages
+        WHERE convo_id = $1 AND seq < $2 AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY epoch DESC, seq DESC
+        LIMIT $3
+        "#,
+    )
+    .bind(convo_id)
+    .bind(around_seq)
+    .bind(before_limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list messages before pivot")?;
+    before.reverse();
+
+    let after = sqlx::query_as::<_, Message>(
+        r#"
+        SELECT id, convo_id, sender_did, message_type, CAST(epoch AS BIGINT), CAST(seq AS BIGINT), ciphertext, created_at, expires_at
+        FROM messages
+        WHERE convo_id = $1 AND seq >= $2 AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY epoch ASC, seq ASC
+        LIMIT $3
+        "#,
+    )
+    .bind(convo_id)
+    .bind(around_seq)
+    .bind(after_limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list messages after pivot")?;
+
+    before.extend(after);
+    Ok(before)
+}
+
+/// List messages strictly between `after_seq` and `before_seq`, ascending,
+/// capped at `limit`. Used by the `BETWEEN` selector.
+pub async fn list_messages_between_seq(
+    pool: &DbPool,
+    convo_id: &str,
+    after_seq: i64,
+    before_seq: i64,
+    limit: i64,
+) -> Result<Vec<Message>> {
+    let messages = sqlx::query_as::<_, Message>(
+        r#"
+        SELECT id, convo_id, sender_did, message_type, CAST(epoch AS BIGINT), CAST(seq AS BIGINT), ciphertext, created_at, expires_at
+        FROM messages
+        WHERE convo_id = $1 AND seq > $2 AND seq < $3 AND (expires_at IS NULL OR expires_at > NOW())
+        ORDER BY epoch ASC, seq ASC
+        LIMIT $4
+        "#,
+    )
+    .bind(convo_id)
+    .bind(after_seq)
+    .bind(before_seq)
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .context("Failed to list messages between sequence numbers")?;
+
+    Ok(messages)
+}
+
 /// Gap detection information
 #[derive(Debug, Clone)]
 pub struct GapInfo {
@@ -842,6 +947,76 @@ pub async fn get_all_key_packages(
     Ok(key_packages)
 }
 
+/// Threshold below which a successful claim should tell the owning device to
+/// replenish its key package pool via `publish_key_package`.
+pub const KEY_PACKAGE_REPLENISH_THRESHOLD: i64 = 5;
+
+/// Result of [`claim_key_package`]: the claimed package plus enough inventory
+/// context for the caller to decide whether to signal replenishment.
+#[derive(Debug, Clone)]
+pub struct ClaimedKeyPackage {
+    pub key_package: KeyPackage,
+    /// Unconsumed, unexpired packages remaining for `owner_did` *after* this claim.
+    pub remaining: i64,
+}
+
+impl ClaimedKeyPackage {
+    pub fn replenish_needed(&self) -> bool {
+        self.remaining < KEY_PACKAGE_REPLENISH_THRESHOLD
+    }
+}
+
+/// Atomically claim one available key package for a user.
+///
+/// Selects the oldest unconsumed, unexpired package with `FOR UPDATE SKIP
+/// LOCKED` so two concurrent adds can never be handed the same package, then
+/// marks it consumed in the same statement. Returns the remaining inventory
+/// count alongside the claimed package so callers can decide whether to
+/// signal [`ClaimedKeyPackage::replenish_needed`].
+pub async fn claim_key_package(
+    pool: &DbPool,
+    did: &str,
+    cipher_suite: &str,
+) -> Result<Option<ClaimedKeyPackage>> {
+    let now = Utc::now();
+
+    let claimed = sqlx::query_as::<_, KeyPackage>(
+        r#"
+        UPDATE key_packages
+        SET consumed_at = $1
+        WHERE key_package_hash = (
+            SELECT key_package_hash
+            FROM key_packages
+            WHERE owner_did = $2
+              AND cipher_suite = $3
+              AND consumed_at IS NULL
+              AND expires_at > $1
+            ORDER BY created_at ASC
+            LIMIT 1
+            FOR UPDATE SKIP LOCKED
+        )
+        RETURNING owner_did, cipher_suite, key_package as key_data, key_package_hash, created_at, expires_at, consumed_at
+        "#,
+    )
+    .bind(now)
+    .bind(did)
+    .bind(cipher_suite)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to claim key package")?;
+
+    let Some(key_package) = claimed else {
+        return Ok(None);
+    };
+
+    let remaining = count_available_key_packages(pool, did).await?;
+
+    Ok(Some(ClaimedKeyPackage {
+        key_package,
+        remaining,
+    }))
+}
+
 /// Mark a key package as consumed
 pub async fn consume_key_package(
     pool: &DbPool,
@@ -890,6 +1065,67 @@ pub async fn mark_key_package_consumed(
     Ok(result.rows_affected() > 0)
 }
 
+/// Count unconsumed, unexpired key packages across all of a user's devices
+/// (all cipher suites) - the low-water-mark figure surfaced by
+/// `GET /api/v1/key-packages/count` and by [`claim_key_package`].
+pub async fn count_available_key_packages(pool: &DbPool, did: &str) -> Result<i64> {
+    let now = Utc::now();
+
+    let count = sqlx::query_scalar::<_, i64>(
+        r#"
+        SELECT COUNT(*)
+        FROM key_packages
+        WHERE owner_did = $1 AND consumed_at IS NULL AND expires_at > $2
+        "#,
+    )
+    .bind(did)
+    .bind(now)
+    .fetch_one(pool)
+    .await
+    .context("Failed to count available key packages")?;
+
+    Ok(count)
+}
+
+/// How long to wait before re-sending a low-inventory notification to the
+/// same user.
+const LOW_INVENTORY_NOTIFICATION_THROTTLE_HOURS: i64 = 24;
+
+/// Whether enough time has passed since the last low-inventory notification
+/// to `did` that another one should be sent.
+pub async fn should_send_low_inventory_notification(pool: &DbPool, did: &str) -> Result<bool> {
+    let cutoff = Utc::now() - chrono::Duration::hours(LOW_INVENTORY_NOTIFICATION_THROTTLE_HOURS);
+
+    let last_notified: Option<DateTime<Utc>> = sqlx::query_scalar(
+        "SELECT notified_at FROM key_package_low_inventory_notices WHERE owner_did = $1",
+    )
+    .bind(did)
+    .fetch_optional(pool)
+    .await
+    .context("Failed to check low inventory notification throttle")?;
+
+    Ok(last_notified.is_none_or(|notified_at| notified_at < cutoff))
+}
+
+/// Record that a low-inventory notification was just sent to `did`, for
+/// [`should_send_low_inventory_notification`] throttling.
+pub async fn record_low_inventory_notification(pool: &DbPool, did: &str) -> Result<()> {
+    sqlx::query(
+        r#"
+        INSERT INTO key_package_low_inventory_notices (owner_did, notified_at)
+        VALUES ($1, $2)
+        ON CONFLICT (owner_did) DO UPDATE SET notified_at = $2
+        "#,
+    )
+    .bind(did)
+    .bind(Utc::now())
+    .execute(pool)
+    .await
+    .context("Failed to record low inventory notification")?;
+
+    Ok(())
+}
+
 /// Count key packages consumed in last N hours
 pub async fn count_consumed_key_packages(
     pool: &DbPool,
@@ -1444,40 +1680,15 @@ pub async fn get_events_after_cursor(
         emitted_at: DateTime<Utc>,
     }
 
-    let events: Vec<EventRow> = if let Some(et) = event_type {
-        sqlx::query_as(
-            r#"
-            SELECT id, payload, emitted_at
-            FROM event_stream
-            WHERE convo_id = $1 AND event_type = $2 AND id > $3
-            ORDER BY id ASC
-            LIMIT $4
-            "#,
-        )
-        .bind(convo_id)
-        .bind(et)
-        .bind(after_cursor)
-        .bind(limit)
-        .fetch_all(pool)
-        .await
-        .context("Failed to get events")?
-    } else {
-        sqlx::query_as(
-            r#"
-            SELECT id, payload, emitted_at
-            FROM event_stream
-            WHERE convo_id = $1 AND id > $2
-            ORDER BY id ASC
-            LIMIT $3
-            "#,
-        )
-        .bind(convo_id)
-        .bind(after_cursor)
-        .bind(limit)
+    let events: Vec<EventRow> = crate::query::SelectBuilder::new("id, payload, emitted_at", "event_stream")
+        .filter("convo_id", convo_id.to_string())
+        .filter_opt("event_type", event_type.map(|et| et.to_string()))
+        .filter_op("id", ">", after_cursor.to_string())
+        .order_by("id ASC")
+        .limit(limit)
         .fetch_all(pool)
         .await
-        .context("Failed to get events")?
-    };
+        .context("Failed to get events")?;
 
     Ok(events
         .into_iter()
@@ -44,6 +44,9 @@ pub enum FederationError {
     #[error("Invalid proof for sequencer transfer")]
     InvalidProof,
 
+    #[error("Peer speaks federation protocol version {peer_version}, below the minimum supported version {min_supported}")]
+    IncompatibleProtocolVersion { peer_version: u32, min_supported: u32 },
+
     #[error("Configuration error: {reason}")]
     ConfigError { reason: String },
 
@@ -71,6 +74,7 @@ impl FederationError {
             Self::DsUnreachable { .. } | Self::ResolutionFailed { .. } | Self::Http(_) => {
                 StatusCode::BAD_GATEWAY
             }
+            Self::IncompatibleProtocolVersion { .. } => StatusCode::UPGRADE_REQUIRED,
             Self::RemoteError { status, .. } => {
                 StatusCode::from_u16(*status).unwrap_or(StatusCode::BAD_GATEWAY)
             }
@@ -95,6 +99,7 @@ impl FederationError {
             Self::RecipientNotFound { .. } => "RecipientNotFound",
             Self::NoKeyPackagesAvailable { .. } => "NoKeyPackagesAvailable",
             Self::InvalidProof => "InvalidProof",
+            Self::IncompatibleProtocolVersion { .. } => "IncompatibleProtocolVersion",
             Self::ConfigError { .. } => "ConfigError",
             Self::Database(_) => "InternalError",
             Self::Http(_) => "NetworkError",
@@ -197,6 +202,14 @@ mod tests {
             .status_code(),
             StatusCode::SERVICE_UNAVAILABLE
         );
+        assert_eq!(
+            FederationError::IncompatibleProtocolVersion {
+                peer_version: 1,
+                min_supported: 2,
+            }
+            .status_code(),
+            StatusCode::UPGRADE_REQUIRED
+        );
     }
 
     #[test]
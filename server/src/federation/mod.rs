@@ -1,10 +1,14 @@
 pub mod ack;
 pub mod errors;
 pub mod mailbox;
+pub mod notify;
 pub mod outbound;
 pub mod peer_policy;
+pub mod protocol;
 pub mod queue;
 pub mod receipt;
+pub mod remote_events;
+pub mod request_signing;
 pub mod resolver;
 pub mod sequencer;
 pub mod service_auth;
@@ -14,8 +18,15 @@ pub mod upstream;
 pub use ack::*;
 pub use errors::FederationError;
 pub use mailbox::FederatedBackend;
+pub use notify::{SequencerCache, SequencerChangeNotification};
+pub use protocol::{
+    fetch_peer_protocol_info, negotiate, NegotiatedProtocol, PeerProtocolInfo, CAPABILITIES,
+    MIN_SUPPORTED_PROTOCOL_VERSION, PROTOCOL_VERSION,
+};
 pub use receipt::*;
-pub use resolver::DsResolver;
+pub use remote_events::{IngestRemoteEventBody, RemoteEphemeralEvent, RemoteNodeRegistry};
+pub use request_signing::{RequestSigner, SignedHeaders};
+pub use resolver::{CachedDsResolver, DsEndpoint, DsResolver, RemoteDsResolver};
 pub use sequencer::{CommitResult, Sequencer};
 pub use service_auth::ServiceAuthClient;
 pub use transfer::{SequencerTransfer, TransferError, TransferResult};
@@ -32,6 +43,10 @@ pub struct FederationConfig {
     /// Fallback DS endpoint for users without a `blue.catbird.mls.profile` record.
     pub default_ds_endpoint: Option<String>,
     pub endpoint_cache_ttl_secs: u64,
+    /// TTL for negatively-cached (failed) DS endpoint resolutions; kept short
+    /// so a transient PLC/PDS outage doesn't wedge a peer out for as long as
+    /// a successful resolution would be cached.
+    pub endpoint_negative_cache_ttl_secs: u64,
     pub outbound_timeout_secs: u64,
     pub outbound_connect_timeout_secs: u64,
 }
@@ -52,6 +67,10 @@ impl FederationConfig {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(3600),
+            endpoint_negative_cache_ttl_secs: std::env::var("ENDPOINT_NEGATIVE_CACHE_TTL")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(30),
             outbound_timeout_secs: std::env::var("OUTBOUND_TIMEOUT")
                 .ok()
                 .and_then(|v| v.parse().ok())
@@ -5,6 +5,7 @@ use tracing::{debug, warn};
 
 use super::ack::DeliveryAck;
 use super::receipt::SequencerReceipt;
+use super::request_signing::SignedHeaders;
 
 /// HTTP client for outbound DS-to-DS calls.
 pub struct OutboundClient {
@@ -65,6 +66,42 @@ impl OutboundClient {
         parse_response(resp, endpoint, method).await
     }
 
+    /// Make an authenticated XRPC procedure call to a remote DS, additionally
+    /// attaching HTTP message signature headers (see
+    /// [`super::request_signing`]) so the receiving DS can verify integrity
+    /// and reject replays independent of the bearer token.
+    ///
+    /// `body` is sent verbatim (not re-serialized) since `signed_headers`'
+    /// content digest was computed over these exact bytes.
+    pub async fn call_procedure_signed(
+        &self,
+        endpoint: &str,
+        method: &str,
+        auth_token: &str,
+        signed_headers: &SignedHeaders,
+        body: Vec<u8>,
+    ) -> Result<DsResponse, OutboundError> {
+        let url = format!("{}/xrpc/{}", endpoint.trim_end_matches('/'), method);
+        debug!(url = %url, method, "Outbound signed DS call");
+
+        let mut req = self
+            .http
+            .post(&url)
+            .header("Authorization", format!("Bearer {auth_token}"))
+            .header("Content-Type", "application/json");
+        for (name, value) in signed_headers.as_header_pairs() {
+            req = req.header(name, value);
+        }
+
+        let resp = req
+            .body(body)
+            .send()
+            .await
+            .map_err(|e| classify_reqwest_error(e, endpoint, method))?;
+
+        parse_response(resp, endpoint, method).await
+    }
+
     /// Make an authenticated XRPC query call to a remote DS.
     pub async fn call_query(
         &self,
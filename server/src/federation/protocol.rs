@@ -0,0 +1,150 @@
+use super::errors::FederationError;
+
+/// Federation protocol version spoken by this DS.
+///
+/// Version 1 is the original, un-negotiated wire protocol: `healthCheck`
+/// carries no `protocolVersion`/`capabilities` fields, and every DS just
+/// assumes every other DS understands whatever it sends. Version 2 adds
+/// this handshake itself; a peer that doesn't echo `protocolVersion` back
+/// is, by definition, on version 1 and negotiates down to it — no
+/// capabilities gated behind the handshake (signed requests, quorum
+/// failover, cursor-based sync) are assumed for it.
+pub const PROTOCOL_VERSION: u32 = 2;
+
+/// Lowest peer protocol version this DS will negotiate down to.
+///
+/// Deliberately *not* pinned to [`PROTOCOL_VERSION`]: a version-1 peer is
+/// simply one that predates this handshake (including one of our own
+/// replicas mid-rollout, per the Postgres-NOTIFY fanout across DS nodes),
+/// and we can still interoperate with it by negotiating an empty
+/// capability set. Bump this up only when a later protocol version drops
+/// support for version-1 peers on purpose, so takeovers are refused for a
+/// peer that's genuinely too old rather than merely not-yet-upgraded.
+pub const MIN_SUPPORTED_PROTOCOL_VERSION: u32 = 1;
+
+/// Capability names this DS advertises in `healthCheck`, gating optional
+/// federation behavior that a peer might not implement yet.
+pub const CAPABILITIES: &[&str] = &["signedRequests", "quorumFailover", "cursorSync"];
+
+/// Protocol version and capabilities a peer DS advertised, parsed from its
+/// `healthCheck` response.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PeerProtocolInfo {
+    pub version: u32,
+    pub capabilities: Vec<String>,
+}
+
+impl PeerProtocolInfo {
+    /// Parse from a `healthCheck` JSON body. A peer that omits
+    /// `protocolVersion` entirely predates this handshake and is treated as
+    /// version 1 with no advertised capabilities.
+    pub fn from_health_check_body(body: &serde_json::Value) -> Self {
+        let version = body
+            .get("protocolVersion")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(1) as u32;
+        let capabilities = body
+            .get("capabilities")
+            .and_then(|v| v.as_array())
+            .map(|arr| {
+                arr.iter()
+                    .filter_map(|c| c.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default();
+        Self { version, capabilities }
+    }
+}
+
+/// Result of negotiating a protocol version and capability set with a peer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NegotiatedProtocol {
+    pub version: u32,
+    pub shared_capabilities: Vec<String>,
+}
+
+/// Negotiate the highest protocol version and capability set this DS and
+/// `peer` both understand.
+///
+/// Returns [`FederationError::IncompatibleProtocolVersion`] if the peer's
+/// version falls below [`MIN_SUPPORTED_PROTOCOL_VERSION`] — callers should
+/// refuse the takeover/transfer rather than proceed with a peer that can't
+/// be trusted to understand it.
+pub fn negotiate(peer: &PeerProtocolInfo) -> Result<NegotiatedProtocol, FederationError> {
+    if peer.version < MIN_SUPPORTED_PROTOCOL_VERSION {
+        return Err(FederationError::IncompatibleProtocolVersion {
+            peer_version: peer.version,
+            min_supported: MIN_SUPPORTED_PROTOCOL_VERSION,
+        });
+    }
+
+    let version = peer.version.min(PROTOCOL_VERSION);
+    let shared_capabilities = CAPABILITIES
+        .iter()
+        .map(|c| c.to_string())
+        .filter(|c| peer.capabilities.iter().any(|p| p == c))
+        .collect();
+
+    Ok(NegotiatedProtocol {
+        version,
+        shared_capabilities,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn negotiate_picks_lower_of_the_two_versions() {
+        let peer = PeerProtocolInfo {
+            version: PROTOCOL_VERSION,
+            capabilities: vec!["signedRequests".to_string()],
+        };
+        let negotiated = negotiate(&peer).unwrap();
+        assert_eq!(negotiated.version, PROTOCOL_VERSION);
+        assert_eq!(negotiated.shared_capabilities, vec!["signedRequests"]);
+    }
+
+    #[test]
+    fn negotiate_accepts_a_legacy_peer_with_no_shared_capabilities() {
+        let peer = PeerProtocolInfo {
+            version: 1,
+            capabilities: vec![],
+        };
+        let negotiated = negotiate(&peer).unwrap();
+        assert_eq!(negotiated.version, 1);
+        assert!(negotiated.shared_capabilities.is_empty());
+    }
+
+    #[test]
+    fn negotiate_refuses_a_peer_below_the_minimum() {
+        let peer = PeerProtocolInfo {
+            version: 0,
+            capabilities: vec![],
+        };
+        let err = negotiate(&peer).unwrap_err();
+        assert!(matches!(
+            err,
+            FederationError::IncompatibleProtocolVersion { peer_version: 0, .. }
+        ));
+    }
+
+    #[test]
+    fn negotiate_drops_capabilities_the_peer_never_advertised() {
+        let peer = PeerProtocolInfo {
+            version: PROTOCOL_VERSION,
+            capabilities: vec!["quorumFailover".to_string(), "somethingUnknown".to_string()],
+        };
+        let negotiated = negotiate(&peer).unwrap();
+        assert_eq!(negotiated.shared_capabilities, vec!["quorumFailover"]);
+    }
+
+    #[test]
+    fn parses_legacy_health_check_body_as_version_one() {
+        let body = serde_json::json!({ "did": "did:web:old.example.com", "version": "1.0.0" });
+        let info = PeerProtocolInfo::from_health_check_body(&body);
+        assert_eq!(info.version, 1);
+        assert!(info.capabilities.is_empty());
+    }
+}
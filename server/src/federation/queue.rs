@@ -1,12 +1,27 @@
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
+use rand::Rng;
 use sqlx::PgPool;
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, warn};
 
 use super::outbound::{OutboundClient, OutboundError};
+use super::request_signing::RequestSigner;
 use crate::auth::AuthMiddleware;
+use crate::metrics;
+
+/// A delivery attempt slower than this is logged and counted as slow, even if
+/// it eventually succeeds - operators need to see degrading peers before they
+/// start timing out outright.
+fn slow_delivery_threshold() -> Duration {
+    let threshold_ms = std::env::var("OUTBOUND_SLOW_DELIVERY_THRESHOLD_MS")
+        .ok()
+        .and_then(|v| v.parse::<u64>().ok())
+        .filter(|v| *v > 0)
+        .unwrap_or(2000);
+    Duration::from_millis(threshold_ms)
+}
 
 // ---------------------------------------------------------------------------
 // Queue item
@@ -34,9 +49,25 @@ pub struct QueueStats {
     pub pending: i64,
     pub delivered: i64,
     pub failed: i64,
+    pub dead_lettered: i64,
     pub total: i64,
 }
 
+/// A dead-lettered delivery: one that exhausted its retry budget without
+/// ever reaching the target DS. Kept separate from plain `failed` (an
+/// explicit rejection from the remote) so an operator can tell "they said no"
+/// from "we never got an answer", and retry the latter.
+#[derive(Debug, Clone)]
+pub struct DeadLetterItem {
+    pub id: String,
+    pub target_ds_did: String,
+    pub target_endpoint: String,
+    pub method: String,
+    pub convo_id: String,
+    pub retry_count: i32,
+    pub last_error: Option<String>,
+}
+
 // ---------------------------------------------------------------------------
 // OutboundQueue
 // ---------------------------------------------------------------------------
@@ -45,13 +76,23 @@ pub struct QueueStats {
 pub struct OutboundQueue {
     pool: PgPool,
     auth_middleware: AuthMiddleware,
+    /// Signs outbound deliveries with the DS's own key for integrity and
+    /// replay protection, independent of the bearer-token service auth JWT.
+    /// `None` when no signing key is configured, in which case deliveries
+    /// fall back to bearer-token auth only.
+    request_signer: Option<Arc<RequestSigner>>,
 }
 
 impl OutboundQueue {
-    pub fn new(pool: PgPool, auth_middleware: AuthMiddleware) -> Self {
+    pub fn new(
+        pool: PgPool,
+        auth_middleware: AuthMiddleware,
+        request_signer: Option<Arc<RequestSigner>>,
+    ) -> Self {
         Self {
             pool,
             auth_middleware,
+            request_signer,
         }
     }
 
@@ -87,6 +128,7 @@ impl OutboundQueue {
         .await?;
 
         debug!(queue_id = %id, target_ds_did, method, convo_id, "Enqueued for retry");
+        metrics::record_outbound_enqueued(method);
         Ok(id)
     }
 
@@ -212,10 +254,42 @@ impl OutboundQueue {
             }
         };
 
-        match outbound
-            .call_procedure(&target_endpoint, &item.method, &token, &body)
-            .await
-        {
+        let attempt_start = Instant::now();
+        let delivery_result = if let Some(signer) = self.request_signer.as_ref() {
+            // Sign and send item.payload verbatim - re-serializing through
+            // `body` here would let the bytes on the wire drift from the
+            // bytes the digest was computed over (different key ordering),
+            // so the receiver's content-digest check would fail.
+            let path = format!("/xrpc/{}", item.method);
+            let signed_headers = signer.sign("POST", &path, &item.payload);
+            outbound
+                .call_procedure_signed(
+                    &target_endpoint,
+                    &item.method,
+                    &token,
+                    &signed_headers,
+                    item.payload.clone(),
+                )
+                .await
+        } else {
+            outbound
+                .call_procedure(&target_endpoint, &item.method, &token, &body)
+                .await
+        };
+        let attempt_duration = attempt_start.elapsed();
+        let is_slow = attempt_duration >= slow_delivery_threshold();
+        metrics::record_outbound_delivery_duration(&item.method, attempt_duration, is_slow);
+        if is_slow {
+            warn!(
+                queue_id = %item.id,
+                method = %item.method,
+                target_ds = %item.target_ds_did,
+                elapsed_ms = attempt_duration.as_millis() as u64,
+                "Outbound queue delivery attempt exceeded slow-delivery threshold"
+            );
+        }
+
+        match delivery_result {
             Ok(resp) if resp.accepted => {
                 debug!(queue_id = %item.id, "Retry delivery succeeded");
                 if let Some(ref ack) = resp.ack {
@@ -281,6 +355,7 @@ impl OutboundQueue {
                         }
                     }
                 }
+                metrics::record_outbound_delivered(&item.method);
                 let _ = self.mark_delivered(&item.id).await;
             }
             Ok(resp) => {
@@ -301,12 +376,27 @@ impl OutboundQueue {
                     .schedule_retry(&item.id, item.retry_count + 1, &e.to_string(), delay)
                     .await;
             }
+            Err(e) if e.is_retryable() => {
+                // Retryable in kind, but the retry budget is spent - this is
+                // the "never got an answer" case a `transferSequencer` that
+                // no participant DS ever acknowledged needs to be found and
+                // retried from, so it gets its own status rather than being
+                // lumped in with explicit remote rejections.
+                error!(
+                    queue_id = %item.id,
+                    retries = item.retry_count,
+                    error = %e,
+                    "Retry budget exhausted, dead-lettering"
+                );
+                metrics::record_outbound_dead_lettered(&item.method);
+                let _ = self.mark_dead_letter(&item.id, &e.to_string()).await;
+            }
             Err(e) => {
                 error!(
                     queue_id = %item.id,
                     retries = item.retry_count,
                     error = %e,
-                    "Non-retryable or max retries exceeded"
+                    "Non-retryable failure"
                 );
                 let _ = self.mark_failed(&item.id, &e.to_string()).await;
             }
@@ -360,6 +450,17 @@ impl OutboundQueue {
         Ok(())
     }
 
+    async fn mark_dead_letter(&self, id: &str, error_msg: &str) -> Result<(), sqlx::Error> {
+        sqlx::query(
+            "UPDATE outbound_queue SET status = 'dead_letter', last_error = $2 WHERE id = $1",
+        )
+        .bind(id)
+        .bind(error_msg)
+        .execute(&self.pool)
+        .await?;
+        Ok(())
+    }
+
     async fn schedule_retry(
         &self,
         id: &str,
@@ -399,11 +500,12 @@ impl OutboundQueue {
 
     /// Queue statistics for monitoring / health endpoints.
     pub async fn stats(&self) -> Result<QueueStats, sqlx::Error> {
-        let row: (i64, i64, i64, i64) = sqlx::query_as(
+        let row: (i64, i64, i64, i64, i64) = sqlx::query_as(
             "SELECT \
                 COUNT(*) FILTER (WHERE status = 'pending'), \
                 COUNT(*) FILTER (WHERE status = 'delivered'), \
                 COUNT(*) FILTER (WHERE status = 'failed'), \
+                COUNT(*) FILTER (WHERE status = 'dead_letter'), \
                 COUNT(*) \
              FROM outbound_queue",
         )
@@ -414,20 +516,74 @@ impl OutboundQueue {
             pending: row.0,
             delivered: row.1,
             failed: row.2,
-            total: row.3,
+            dead_lettered: row.3,
+            total: row.4,
         })
     }
+
+    /// List dead-lettered items, most recent first, so an operator can see
+    /// which deliveries (e.g. a `transferSequencer` broadcast) never reached
+    /// their target DS.
+    pub async fn list_dead_letters(&self, limit: i64) -> Result<Vec<DeadLetterItem>, sqlx::Error> {
+        let rows: Vec<(String, String, String, String, String, i32, Option<String>)> =
+            sqlx::query_as(
+                "SELECT id, target_ds_did, target_endpoint, method, convo_id, retry_count, last_error \
+             FROM outbound_queue \
+             WHERE status = 'dead_letter' \
+             ORDER BY created_at DESC \
+             LIMIT $1",
+            )
+            .bind(limit)
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(
+                |(id, target_ds_did, target_endpoint, method, convo_id, retry_count, last_error)| {
+                    DeadLetterItem {
+                        id,
+                        target_ds_did,
+                        target_endpoint,
+                        method,
+                        convo_id,
+                        retry_count,
+                        last_error,
+                    }
+                },
+            )
+            .collect())
+    }
+
+    /// Reset a dead-lettered item back to `pending` with a fresh retry
+    /// budget, so an operator can retry it (e.g. once a downed peer is back)
+    /// without re-deriving the original payload.
+    pub async fn retry_dead_letter(&self, id: &str) -> Result<bool, sqlx::Error> {
+        let result = sqlx::query(
+            "UPDATE outbound_queue \
+             SET status = 'pending', retry_count = 0, next_retry_at = NOW() \
+             WHERE id = $1 AND status = 'dead_letter'",
+        )
+        .bind(id)
+        .execute(&self.pool)
+        .await?;
+        Ok(result.rows_affected() > 0)
+    }
 }
 
 // ---------------------------------------------------------------------------
 // Backoff
 // ---------------------------------------------------------------------------
 
-/// Exponential backoff: 5 s → 10 s → 20 s → 40 s → 80 s (capped at 5 min).
+/// Exponential backoff with full jitter: 5 s → 10 s → 20 s → 40 s → 80 s
+/// (capped at 5 min) as an upper bound, with the actual delay drawn uniformly
+/// from `[0, upper_bound]` so a batch of items retrying at once doesn't all
+/// wake and hammer the same peer in lockstep.
 fn backoff_delay(retry_count: i32) -> Duration {
     let base = 5u64;
-    let delay = base.saturating_mul(2u64.saturating_pow(retry_count as u32));
-    Duration::from_secs(delay.min(300))
+    let upper_bound = base.saturating_mul(2u64.saturating_pow(retry_count as u32)).min(300);
+    let jittered = rand::thread_rng().gen_range(0..=upper_bound);
+    Duration::from_secs(jittered)
 }
 
 fn did_web_to_endpoint(did: &str) -> Option<String> {
@@ -441,15 +597,25 @@ mod tests {
     use super::*;
 
     #[test]
-    fn backoff_values() {
-        assert_eq!(backoff_delay(0), Duration::from_secs(5));
-        assert_eq!(backoff_delay(1), Duration::from_secs(10));
-        assert_eq!(backoff_delay(2), Duration::from_secs(20));
-        assert_eq!(backoff_delay(3), Duration::from_secs(40));
-        assert_eq!(backoff_delay(4), Duration::from_secs(80));
-        assert_eq!(backoff_delay(5), Duration::from_secs(160));
-        assert_eq!(backoff_delay(6), Duration::from_secs(300)); // capped
-        assert_eq!(backoff_delay(10), Duration::from_secs(300)); // still capped
+    fn backoff_values_stay_within_jittered_bounds() {
+        // Full jitter means each call only promises an upper bound, not an
+        // exact value - assert the envelope instead of a fixed delay.
+        let cases = [
+            (0, 5),
+            (1, 10),
+            (2, 20),
+            (3, 40),
+            (4, 80),
+            (5, 160),
+            (6, 300),   // capped
+            (10, 300),  // still capped
+        ];
+        for (retry_count, upper_bound_secs) in cases {
+            for _ in 0..50 {
+                let delay = backoff_delay(retry_count);
+                assert!(delay <= Duration::from_secs(upper_bound_secs));
+            }
+        }
     }
 
     #[test]
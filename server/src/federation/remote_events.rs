@@ -0,0 +1,292 @@
+//! Cross-DS fan-out for ephemeral events (reactions, typing indicators).
+//!
+//! Reactions and typing indicators are handled entirely within the local DS:
+//! they're applied to the local database/actor state and pushed to local
+//! subscribers via SSE and [`crate::actors::ConvoMessage::Notify`]. Members
+//! hosted on a different delivery service never see them unless something
+//! forwards the event across the federation boundary. [`RemoteNodeRegistry`]
+//! is that something - given a conversation's member list, it groups the
+//! non-local members by home DS endpoint (reusing [`DsResolver`], the same
+//! component `ds::deliverMessage`'s sequencer path relies on) and posts the
+//! event once per node, with a per-node circuit breaker so one unreachable
+//! peer can't stall delivery to the rest.
+
+use dashmap::DashMap;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use super::outbound::OutboundClient;
+use super::resolver::DsResolver;
+use super::service_auth::ServiceAuthClient;
+use crate::identity::{canonical_did, dids_equivalent};
+
+/// Lexicon method remote nodes expose to receive a forwarded ephemeral event.
+const INGEST_NSID: &str = "blue.catbird.mls.ds.ingestRemoteEvent";
+
+/// How many times to attempt delivery to a single node before giving up and
+/// letting the circuit breaker record the failure.
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Consecutive failures before a node's circuit opens.
+const FAILURE_THRESHOLD: u32 = 5;
+
+/// How long an open circuit stays open before the next attempt is allowed
+/// through again.
+const OPEN_COOLDOWN: Duration = Duration::from_secs(60);
+
+/// An ephemeral event being forwarded to a remote DS node.
+///
+/// Mirrors [`crate::actors::ConvoEvent`]'s reaction/typing variants, but
+/// carries its own type since it crosses the wire to a different DS rather
+/// than fanning out to in-process long-poll subscribers.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RemoteEphemeralEvent {
+    Reaction {
+        message_id: String,
+        did: String,
+        reaction: String,
+        action: String,
+    },
+    Typing {
+        did: String,
+        is_typing: bool,
+    },
+}
+
+impl RemoteEphemeralEvent {
+    /// A short tag used when building the idempotency key, so a reaction add
+    /// and a reaction remove for the same message/reactor don't collide.
+    fn kind_tag(&self) -> &'static str {
+        match self {
+            Self::Reaction { action, .. } if action == "remove" => "reaction-remove",
+            Self::Reaction { .. } => "reaction-add",
+            Self::Typing { .. } => "typing",
+        }
+    }
+
+    fn actor_did(&self) -> &str {
+        match self {
+            Self::Reaction { did, .. } => did,
+            Self::Typing { did, .. } => did,
+        }
+    }
+
+    /// The message a reaction is against, so the idempotency key can't
+    /// collide across different messages. `None` for typing, which has no
+    /// message to scope to.
+    fn message_id(&self) -> Option<&str> {
+        match self {
+            Self::Reaction { message_id, .. } => Some(message_id),
+            Self::Typing { .. } => None,
+        }
+    }
+
+    /// The reaction string (e.g. an emoji), so the idempotency key can't
+    /// collide across different reactions to the same message. `None` for
+    /// typing.
+    fn reaction_value(&self) -> Option<&str> {
+        match self {
+            Self::Reaction { reaction, .. } => Some(reaction),
+            Self::Typing { .. } => None,
+        }
+    }
+}
+
+/// Body posted to a remote node's `ingestRemoteEvent` endpoint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct IngestRemoteEventBody {
+    pub convo_id: String,
+    /// `convo_id + message_id (if any) + reactor DID + event kind`, so the
+    /// receiver can drop duplicates from retried deliveries.
+    pub idempotency_key: String,
+    #[serde(flatten)]
+    pub event: RemoteEphemeralEvent,
+}
+
+/// Per-node failure tracking for the circuit breaker.
+#[derive(Debug, Default)]
+struct CircuitBreaker {
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+}
+
+impl CircuitBreaker {
+    fn is_open(&self) -> bool {
+        self.opened_at
+            .is_some_and(|t| t.elapsed() < OPEN_COOLDOWN)
+    }
+
+    fn record_success(&mut self) {
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+    }
+
+    fn record_failure(&mut self) {
+        self.consecutive_failures += 1;
+        if self.consecutive_failures >= FAILURE_THRESHOLD {
+            self.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+/// Exponential backoff between delivery attempts to the same node: 1s, 2s, 4s.
+fn retry_backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64.saturating_shl(attempt))
+}
+
+/// Maps conversation members to their home DS and fans ephemeral events out
+/// to the ones hosted elsewhere.
+///
+/// Holds a single shared [`OutboundClient`] (and, when configured, a single
+/// shared [`ServiceAuthClient`]) reused across every outbound call - no
+/// client is built per-request or per-node.
+pub struct RemoteNodeRegistry {
+    resolver: Arc<dyn DsResolver>,
+    outbound: OutboundClient,
+    auth: Option<Arc<ServiceAuthClient>>,
+    breakers: DashMap<String, CircuitBreaker>,
+}
+
+impl RemoteNodeRegistry {
+    pub fn new(
+        resolver: Arc<dyn DsResolver>,
+        outbound: OutboundClient,
+        auth: Option<Arc<ServiceAuthClient>>,
+    ) -> Self {
+        Self {
+            resolver,
+            outbound,
+            auth,
+            breakers: DashMap::new(),
+        }
+    }
+
+    /// Fan `event` out to every remote node hosting a member of `member_dids`,
+    /// once per node. Spawns a background task per node and returns
+    /// immediately - callers should not block the request path on cross-DS
+    /// delivery of a best-effort ephemeral event.
+    pub fn broadcast(
+        self: &Arc<Self>,
+        convo_id: String,
+        member_dids: Vec<String>,
+        event: RemoteEphemeralEvent,
+    ) {
+        let registry = Arc::clone(self);
+        tokio::spawn(async move {
+            registry.broadcast_inner(&convo_id, &member_dids, event).await;
+        });
+    }
+
+    async fn broadcast_inner(
+        &self,
+        convo_id: &str,
+        member_dids: &[String],
+        event: RemoteEphemeralEvent,
+    ) {
+        let originator = canonical_did(event.actor_did()).to_string();
+
+        // Group non-local members by home DS endpoint so each node gets the
+        // event exactly once, regardless of how many of its users are in
+        // this conversation.
+        let mut nodes: std::collections::HashMap<String, String> = std::collections::HashMap::new();
+        for member in member_dids {
+            if dids_equivalent(member, &originator) {
+                continue;
+            }
+            match self.resolver.resolve(member).await {
+                Ok(endpoint) if !self.resolver.is_self(&endpoint.did) => {
+                    nodes
+                        .entry(endpoint.endpoint)
+                        .or_insert(endpoint.did);
+                }
+                Ok(_) => {} // member is local to this DS, nothing to forward
+                Err(e) => {
+                    warn!(convo_id, error = %e, "Failed to resolve remote member's home DS for fan-out");
+                }
+            }
+        }
+
+        if nodes.is_empty() {
+            return;
+        }
+
+        let idempotency_key = format!(
+            "{}:{}:{}:{}:{}",
+            convo_id,
+            event.message_id().unwrap_or("-"),
+            originator,
+            event.reaction_value().unwrap_or("-"),
+            event.kind_tag(),
+        );
+        let body = IngestRemoteEventBody {
+            convo_id: convo_id.to_string(),
+            idempotency_key,
+            event,
+        };
+
+        for (endpoint, ds_did) in nodes {
+            if self.breaker_is_open(&endpoint) {
+                warn!(endpoint, "Circuit open for remote node, skipping ephemeral event delivery");
+                continue;
+            }
+
+            match self.deliver_with_retry(&endpoint, &ds_did, &body).await {
+                Ok(()) => self.record_outcome(&endpoint, true),
+                Err(e) => {
+                    warn!(endpoint, error = %e, "Giving up on remote event delivery after retries");
+                    self.record_outcome(&endpoint, false);
+                }
+            }
+        }
+    }
+
+    async fn deliver_with_retry(
+        &self,
+        endpoint: &str,
+        ds_did: &str,
+        body: &IngestRemoteEventBody,
+    ) -> Result<(), super::outbound::OutboundError> {
+        let mut last_err = None;
+
+        for attempt in 0..MAX_DELIVERY_ATTEMPTS {
+            if attempt > 0 {
+                tokio::time::sleep(retry_backoff(attempt - 1)).await;
+            }
+
+            let token = match &self.auth {
+                Some(auth) => auth.sign_request(ds_did, INGEST_NSID).unwrap_or_default(),
+                None => String::new(),
+            };
+
+            match self
+                .outbound
+                .call_procedure(endpoint, INGEST_NSID, &token, body)
+                .await
+            {
+                Ok(_) => return Ok(()),
+                Err(e) if e.is_retryable() => last_err = Some(e),
+                Err(e) => return Err(e),
+            }
+        }
+
+        Err(last_err.expect("loop runs at least once"))
+    }
+
+    fn breaker_is_open(&self, endpoint: &str) -> bool {
+        self.breakers
+            .get(endpoint)
+            .is_some_and(|b| b.is_open())
+    }
+
+    fn record_outcome(&self, endpoint: &str, success: bool) {
+        let mut breaker = self.breakers.entry(endpoint.to_string()).or_default();
+        if success {
+            breaker.record_success();
+        } else {
+            breaker.record_failure();
+        }
+    }
+}
@@ -1,6 +1,7 @@
+use async_trait::async_trait;
+use moka::future::Cache;
 use once_cell::sync::Lazy;
-use sqlx::PgPool;
-use std::time::Duration;
+use std::{sync::Arc, time::Duration};
 use tracing::{debug, info};
 
 use super::errors::FederationError;
@@ -15,147 +16,83 @@ pub struct DsEndpoint {
 }
 
 /// Resolves a user's DID to their DS endpoint.
-pub struct DsResolver {
-    pool: PgPool,
+///
+/// Implementations are pluggable so code that only needs "a DID resolves to
+/// an endpoint" (e.g. `request_failover`'s quorum vote, or `upstream`'s
+/// connection dialer) can be unit-tested against an in-memory stand-in
+/// instead of hitting PLC/did:web over the network. [`RemoteDsResolver`] is
+/// the production implementation; wrap it in [`CachedDsResolver`] to add
+/// TTL caching.
+#[async_trait]
+pub trait DsResolver: Send + Sync {
+    /// Resolve a user's DS endpoint.
+    async fn resolve(&self, user_did: &str) -> Result<DsEndpoint, FederationError>;
+
+    /// Get this DS's DID.
+    fn self_did(&self) -> &str;
+
+    /// Get this DS's endpoint URL.
+    fn self_endpoint(&self) -> &str;
+
+    /// Check if a DID refers to this DS.
+    fn is_self(&self, ds_did: &str) -> bool {
+        canonical_did(ds_did) == canonical_did(self.self_did())
+    }
+
+    /// Resolve multiple DIDs, returning a vec of (DID, result) pairs.
+    async fn resolve_many(&self, dids: &[String]) -> Vec<(String, Result<DsEndpoint, FederationError>)> {
+        let mut results = Vec::with_capacity(dids.len());
+        for did in dids {
+            let result = self.resolve(did).await;
+            results.push((did.clone(), result));
+        }
+        results
+    }
+}
+
+/// Network-resolving [`DsResolver`]: looks a DID up via its
+/// `blue.catbird.mls.profile` repo record, falling back to a configured
+/// default DS. Carries no cache of its own — wrap it in [`CachedDsResolver`]
+/// for that.
+pub struct RemoteDsResolver {
     http: reqwest::Client,
     self_did: String,
     self_endpoint: String,
     default_ds: Option<String>,
-    cache_ttl_secs: i64,
 }
 
-impl DsResolver {
+impl RemoteDsResolver {
     pub fn new(
-        pool: PgPool,
         http: reqwest::Client,
         self_did: String,
         self_endpoint: String,
         default_ds: Option<String>,
-        cache_ttl_secs: u64,
     ) -> Self {
         Self {
-            pool,
             http,
             self_did,
             self_endpoint,
             default_ds,
-            cache_ttl_secs: cache_ttl_secs as i64,
         }
     }
 
-    /// Check if a DID refers to this DS.
-    pub fn is_self(&self, ds_did: &str) -> bool {
-        canonical_did(ds_did) == canonical_did(&self.self_did)
-    }
-
-    /// Get this DS's DID.
-    pub fn self_did(&self) -> &str {
-        &self.self_did
-    }
-
-    /// Get this DS's endpoint URL.
-    pub fn self_endpoint(&self) -> &str {
-        &self.self_endpoint
-    }
-
-    /// Resolve a user's DS endpoint. Cache-first, then repo record, then fallback.
-    pub async fn resolve(&self, user_did: &str) -> Result<DsEndpoint, FederationError> {
-        // Check if it's us
-        if canonical_did(user_did) == canonical_did(&self.self_did) {
-            return Ok(DsEndpoint {
-                did: self.self_did.clone(),
-                endpoint: self.self_endpoint.clone(),
-                supported_cipher_suites: None,
-            });
-        }
-
-        // Check cache
-        if let Some(cached) = self.get_cached(user_did).await? {
-            return Ok(cached);
+    /// Build an HTTP client for a [`RemoteDsResolver`], optionally overriding
+    /// DNS resolution.
+    ///
+    /// Lets private deployments point `did:web` and PDS resolution at an
+    /// internal resolver (split-horizon DNS, service mesh) instead of public
+    /// DNS, the same way Vaultwarden's icon-fetch client accepts a custom
+    /// resolver for air-gapped instances. Requires reqwest's `hickory-dns` (or
+    /// an equivalent `Resolve` impl) to actually be wired up; omit for the
+    /// default system resolver.
+    pub fn build_http_client(
+        dns_resolver: Option<Arc<dyn reqwest::dns::Resolve>>,
+    ) -> reqwest::Result<reqwest::Client> {
+        let mut builder = reqwest::Client::builder().timeout(Duration::from_secs(10));
+        if let Some(resolver) = dns_resolver {
+            builder = builder.dns_resolver(resolver);
         }
-
-        // Resolve from repo record (blue.catbird.mls.profile)
-        match self.resolve_from_repo(user_did).await {
-            Ok(endpoint) => {
-                self.cache_endpoint(&endpoint).await?;
-                return Ok(endpoint);
-            }
-            Err(e) => {
-                debug!(did = %crate::crypto::redact_for_log(user_did), error = %e, "Repo resolution failed, trying fallback");
-            }
-        }
-
-        // Fallback to default DS
-        if let Some(ref default) = self.default_ds {
-            info!(
-                did = %crate::crypto::redact_for_log(user_did),
-                default_ds = default,
-                "Using default DS fallback"
-            );
-            return Ok(DsEndpoint {
-                did: user_did.to_string(),
-                endpoint: default.clone(),
-                supported_cipher_suites: None,
-            });
-        }
-
-        Err(FederationError::EndpointNotFound {
-            did: user_did.to_string(),
-        })
-    }
-
-    /// Resolve multiple DIDs, returning a vec of (DID, result) pairs.
-    pub async fn resolve_many(
-        &self,
-        dids: &[String],
-    ) -> Vec<(String, Result<DsEndpoint, FederationError>)> {
-        let mut results = Vec::with_capacity(dids.len());
-        for did in dids {
-            let result = self.resolve(did).await;
-            results.push((did.clone(), result));
-        }
-        results
-    }
-
-    async fn get_cached(&self, did: &str) -> Result<Option<DsEndpoint>, FederationError> {
-        let row = sqlx::query_as::<_, (String, String, Option<String>)>(
-            "SELECT did, endpoint, supported_cipher_suites \
-       FROM ds_endpoints WHERE did = $1 AND expires_at > NOW()",
-        )
-        .bind(did)
-        .fetch_optional(&self.pool)
-        .await?;
-
-        Ok(row.map(|(did, endpoint, suites)| DsEndpoint {
-            did,
-            endpoint,
-            supported_cipher_suites: suites.and_then(|s| serde_json::from_str(&s).ok()),
-        }))
-    }
-
-    async fn cache_endpoint(&self, endpoint: &DsEndpoint) -> Result<(), FederationError> {
-        let suites_json = endpoint
-            .supported_cipher_suites
-            .as_ref()
-            .and_then(|s| serde_json::to_string(s).ok());
-
-        sqlx::query(
-      "INSERT INTO ds_endpoints (did, endpoint, supported_cipher_suites, resolved_at, expires_at) \
-       VALUES ($1, $2, $3, NOW(), NOW() + make_interval(secs => $4)) \
-       ON CONFLICT (did) DO UPDATE SET \
-         endpoint = $2, \
-         supported_cipher_suites = $3, \
-         resolved_at = NOW(), \
-         expires_at = NOW() + make_interval(secs => $4)",
-    )
-    .bind(&endpoint.did)
-    .bind(&endpoint.endpoint)
-    .bind(&suites_json)
-    .bind(self.cache_ttl_secs as f64)
-    .execute(&self.pool)
-    .await?;
-
-        Ok(())
+        builder.build()
     }
 
     /// Resolve DS endpoint from the user's repo record (blue.catbird.mls.profile).
@@ -293,26 +230,129 @@ impl DsResolver {
         })
     }
 
-    /// Invalidate cache entry for a DID.
-    pub async fn invalidate(&self, did: &str) -> Result<(), FederationError> {
-        sqlx::query("DELETE FROM ds_endpoints WHERE did = $1")
-            .bind(did)
-            .execute(&self.pool)
-            .await?;
-        Ok(())
+    async fn validate_remote_url(&self, url_str: &str) -> Result<(), FederationError> {
+        let parsed = validate_endpoint_url(url_str)?;
+        validate_resolved_host_is_public(&parsed).await
+    }
+}
+
+#[async_trait]
+impl DsResolver for RemoteDsResolver {
+    /// Resolve a user's DS endpoint: ourselves, then the repo record, then
+    /// the configured default DS.
+    async fn resolve(&self, user_did: &str) -> Result<DsEndpoint, FederationError> {
+        if canonical_did(user_did) == canonical_did(&self.self_did) {
+            return Ok(DsEndpoint {
+                did: self.self_did.clone(),
+                endpoint: self.self_endpoint.clone(),
+                supported_cipher_suites: None,
+            });
+        }
+
+        match self.resolve_from_repo(user_did).await {
+            Ok(endpoint) => return Ok(endpoint),
+            Err(e) => {
+                debug!(did = %crate::crypto::redact_for_log(user_did), error = %e, "Repo resolution failed, trying fallback");
+            }
+        }
+
+        if let Some(ref default) = self.default_ds {
+            info!(
+                did = %crate::crypto::redact_for_log(user_did),
+                default_ds = default,
+                "Using default DS fallback"
+            );
+            return Ok(DsEndpoint {
+                did: user_did.to_string(),
+                endpoint: default.clone(),
+                supported_cipher_suites: None,
+            });
+        }
+
+        Err(FederationError::EndpointNotFound {
+            did: user_did.to_string(),
+        })
+    }
+
+    fn self_did(&self) -> &str {
+        &self.self_did
     }
 
-    /// Clean up expired cache entries.
-    pub async fn cleanup_expired(&self) -> Result<u64, FederationError> {
-        let result = sqlx::query("DELETE FROM ds_endpoints WHERE expires_at < NOW()")
-            .execute(&self.pool)
-            .await?;
-        Ok(result.rows_affected())
+    fn self_endpoint(&self) -> &str {
+        &self.self_endpoint
     }
+}
 
-    async fn validate_remote_url(&self, url_str: &str) -> Result<(), FederationError> {
-        let parsed = validate_endpoint_url(url_str)?;
-        validate_resolved_host_is_public(&parsed).await
+/// Wraps any [`DsResolver`] with a TTL cache: a configurable positive TTL
+/// for successful resolutions, and a shorter negative TTL for failures, so a
+/// peer with a broken `did:web` record or unreachable PLC directory doesn't
+/// force a fresh resolution attempt on every call while it's down.
+pub struct CachedDsResolver {
+    inner: Arc<dyn DsResolver>,
+    positive: Cache<String, DsEndpoint>,
+    negative: Cache<String, ()>,
+}
+
+impl CachedDsResolver {
+    pub fn new(inner: Arc<dyn DsResolver>, positive_ttl_secs: u64, negative_ttl_secs: u64) -> Self {
+        Self {
+            inner,
+            positive: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(Duration::from_secs(positive_ttl_secs))
+                .build(),
+            negative: Cache::builder()
+                .max_capacity(10_000)
+                .time_to_live(Duration::from_secs(negative_ttl_secs))
+                .build(),
+        }
+    }
+
+    /// Drop any cached result (positive or negative) for a DID, forcing a
+    /// fresh resolution on the next call.
+    pub async fn invalidate(&self, did: &str) {
+        self.positive.invalidate(did).await;
+        self.negative.invalidate(did).await;
+    }
+}
+
+#[async_trait]
+impl DsResolver for CachedDsResolver {
+    async fn resolve(&self, user_did: &str) -> Result<DsEndpoint, FederationError> {
+        if self.is_self(user_did) {
+            return self.inner.resolve(user_did).await;
+        }
+
+        if let Some(cached) = self.positive.get(user_did).await {
+            return Ok(cached);
+        }
+        if self.negative.get(user_did).await.is_some() {
+            debug!(did = %crate::crypto::redact_for_log(user_did), "DS endpoint negative-cache hit");
+            return Err(FederationError::EndpointNotFound {
+                did: user_did.to_string(),
+            });
+        }
+
+        match self.inner.resolve(user_did).await {
+            Ok(endpoint) => {
+                self.positive
+                    .insert(user_did.to_string(), endpoint.clone())
+                    .await;
+                Ok(endpoint)
+            }
+            Err(e) => {
+                self.negative.insert(user_did.to_string(), ()).await;
+                Err(e)
+            }
+        }
+    }
+
+    fn self_did(&self) -> &str {
+        self.inner.self_did()
+    }
+
+    fn self_endpoint(&self) -> &str {
+        self.inner.self_endpoint()
     }
 }
 
@@ -615,4 +655,115 @@ mod tests {
         assert_eq!(cloned.endpoint, ep.endpoint);
         assert_eq!(cloned.supported_cipher_suites, ep.supported_cipher_suites);
     }
+
+    // -- CachedDsResolver tests --
+
+    /// In-memory [`DsResolver`] stand-in: resolves whatever's in `endpoints`,
+    /// counting calls so tests can assert the cache actually saved a trip.
+    struct StaticDsResolver {
+        self_did: String,
+        self_endpoint: String,
+        endpoints: std::collections::HashMap<String, DsEndpoint>,
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    impl StaticDsResolver {
+        fn new(self_did: &str, self_endpoint: &str) -> Self {
+            Self {
+                self_did: self_did.to_string(),
+                self_endpoint: self_endpoint.to_string(),
+                endpoints: std::collections::HashMap::new(),
+                calls: std::sync::atomic::AtomicUsize::new(0),
+            }
+        }
+
+        fn with_endpoint(mut self, did: &str, ep: DsEndpoint) -> Self {
+            self.endpoints.insert(did.to_string(), ep);
+            self
+        }
+
+        fn call_count(&self) -> usize {
+            self.calls.load(std::sync::atomic::Ordering::SeqCst)
+        }
+    }
+
+    #[async_trait]
+    impl DsResolver for StaticDsResolver {
+        async fn resolve(&self, user_did: &str) -> Result<DsEndpoint, FederationError> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            self.endpoints
+                .get(user_did)
+                .cloned()
+                .ok_or_else(|| FederationError::EndpointNotFound {
+                    did: user_did.to_string(),
+                })
+        }
+
+        fn self_did(&self) -> &str {
+            &self.self_did
+        }
+
+        fn self_endpoint(&self) -> &str {
+            &self.self_endpoint
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cached_resolver_hits_inner_once() {
+        let inner = StaticDsResolver::new("did:web:self.example.com", "https://self.example.com")
+            .with_endpoint(
+                "did:web:alice.example.com",
+                DsEndpoint {
+                    did: "did:web:alice.example.com".to_string(),
+                    endpoint: "https://alice-ds.example.com".to_string(),
+                    supported_cipher_suites: None,
+                },
+            );
+        let cached = CachedDsResolver::new(Arc::new(inner), 60, 30);
+
+        let first = cached.resolve("did:web:alice.example.com").await.unwrap();
+        let second = cached.resolve("did:web:alice.example.com").await.unwrap();
+        assert_eq!(first.endpoint, second.endpoint);
+    }
+
+    #[tokio::test]
+    async fn test_cached_resolver_caches_failure() {
+        let inner = Arc::new(StaticDsResolver::new(
+            "did:web:self.example.com",
+            "https://self.example.com",
+        ));
+        let cached = CachedDsResolver::new(inner.clone(), 60, 30);
+
+        assert!(cached.resolve("did:web:unknown.example.com").await.is_err());
+        assert!(cached.resolve("did:web:unknown.example.com").await.is_err());
+        // Second call should have been served from the negative cache.
+        assert_eq!(inner.call_count(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_cached_resolver_invalidate_clears_negative_cache() {
+        let inner = Arc::new(StaticDsResolver::new(
+            "did:web:self.example.com",
+            "https://self.example.com",
+        ));
+        let cached = CachedDsResolver::new(inner.clone(), 60, 30);
+
+        assert!(cached.resolve("did:web:unknown.example.com").await.is_err());
+        cached.invalidate("did:web:unknown.example.com").await;
+        assert!(cached.resolve("did:web:unknown.example.com").await.is_err());
+        assert_eq!(inner.call_count(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_cached_resolver_bypasses_cache_for_self() {
+        let inner = Arc::new(StaticDsResolver::new(
+            "did:web:self.example.com",
+            "https://self.example.com",
+        ));
+        let cached = CachedDsResolver::new(inner.clone(), 60, 30);
+
+        cached.resolve("did:web:self.example.com").await.unwrap();
+        cached.resolve("did:web:self.example.com").await.unwrap();
+        assert_eq!(inner.call_count(), 2);
+    }
 }
@@ -1,6 +1,7 @@
 use sqlx::PgPool;
 use tracing::{info, warn};
 
+use super::notify::notify_sequencer_change;
 use crate::identity::canonical_did;
 
 /// Handles sequencer role transfer between DSes.
@@ -193,6 +194,107 @@ impl SequencerTransfer {
         })
     }
 
+    /// Forcefully assume the sequencer role (same CAS as
+    /// [`Self::assume_sequencer_role`]) and advance the epoch in one
+    /// transaction, issuing a `NOTIFY` on the sequencer-change channel
+    /// before committing. Doing both in a single transaction means other app
+    /// instances watching that channel never observe a sequencer
+    /// reassignment without its epoch bump, or vice versa.
+    pub async fn assume_sequencer_role_and_advance_epoch(
+        &self,
+        convo_id: &str,
+        expected_sequencer: &str,
+    ) -> Result<(TransferResult, i32), TransferError> {
+        let mut tx = self.pool.begin().await.map_err(TransferError::Database)?;
+
+        let row = sqlx::query_as::<_, (Option<String>, Option<i32>)>(
+            "SELECT sequencer_ds, current_epoch FROM conversations WHERE id = $1 FOR UPDATE",
+        )
+        .bind(convo_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(TransferError::Database)?;
+
+        let already_sequencer = match &row {
+            None => return Err(TransferError::ConversationNotFound(convo_id.to_string())),
+            Some((Some(ds), _)) => canonical_did(ds) == canonical_did(&self.self_did),
+            Some((None, _)) => false,
+        };
+
+        if already_sequencer {
+            let epoch = row.and_then(|(_, e)| e).unwrap_or(0);
+            tx.commit().await.map_err(TransferError::Database)?;
+            return Ok((
+                TransferResult::Accepted {
+                    convo_id: convo_id.to_string(),
+                },
+                epoch,
+            ));
+        }
+
+        let has_members: bool = sqlx::query_scalar(
+            "SELECT EXISTS(SELECT 1 FROM members WHERE convo_id = $1 AND left_at IS NULL AND COALESCE(split_part(ds_did, '#', 1), $2) = $2)",
+        )
+        .bind(convo_id)
+        .bind(&self.self_did)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(TransferError::Database)?;
+
+        if !has_members {
+            return Err(TransferError::NotAuthorized {
+                convo_id: convo_id.to_string(),
+                ds_did: self.self_did.clone(),
+            });
+        }
+
+        let result = sqlx::query(
+            "UPDATE conversations SET sequencer_ds = $2 WHERE id = $1 AND (sequencer_ds = $3 OR sequencer_ds IS NULL)",
+        )
+        .bind(convo_id)
+        .bind(&self.self_did)
+        .bind(expected_sequencer)
+        .execute(&mut *tx)
+        .await
+        .map_err(TransferError::Database)?;
+
+        if result.rows_affected() == 0 {
+            return Err(TransferError::NotCurrentSequencer {
+                convo_id: convo_id.to_string(),
+                current_sequencer: "unknown (changed during failover)".to_string(),
+            });
+        }
+
+        let new_epoch: i32 = sqlx::query_scalar(
+            "UPDATE conversations SET current_epoch = current_epoch + 1 WHERE id = $1 RETURNING current_epoch",
+        )
+        .bind(convo_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(TransferError::Database)?;
+
+        notify_sequencer_change(&mut *tx, convo_id, &self.self_did, new_epoch)
+            .await
+            .map_err(TransferError::Database)?;
+
+        tx.commit().await.map_err(TransferError::Database)?;
+
+        warn!(
+            convo_id,
+            new_sequencer = %self.self_did,
+            previous_sequencer = %expected_sequencer,
+            new_epoch,
+            "Assumed sequencer role via failover and advanced epoch"
+        );
+
+        Ok((
+            TransferResult::Accepted {
+                convo_id: convo_id.to_string(),
+            },
+            new_epoch,
+        ))
+    }
+
     /// Pick a new sequencer from the conversation's members.
     /// Prefers the oldest admin, falling back to the oldest member.
     pub async fn pick_new_sequencer(
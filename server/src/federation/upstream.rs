@@ -80,7 +80,7 @@ struct UpstreamConnection {
 // ---------------------------------------------------------------------------
 
 pub struct UpstreamManager {
-    resolver: Arc<DsResolver>,
+    resolver: Arc<dyn DsResolver>,
     auth: Arc<ServiceAuthClient>,
     http: reqwest::Client,
     self_did: String,
@@ -93,7 +93,7 @@ pub struct UpstreamManager {
 
 impl UpstreamManager {
     pub fn new(
-        resolver: Arc<DsResolver>,
+        resolver: Arc<dyn DsResolver>,
         auth: Arc<ServiceAuthClient>,
         self_did: String,
         self_endpoint: String,
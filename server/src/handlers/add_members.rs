@@ -441,8 +441,8 @@ pub async fn add_members(
                                 available
                             );
 
-                            // Notify if below threshold (5 packages)
-                            if available < 5 {
+                            // Notify if below the replenishment threshold
+                            if available < crate::db::KEY_PACKAGE_REPLENISH_THRESHOLD {
                                 // Check if we should send notification (throttling)
                                 match crate::db::should_send_low_inventory_notification(&pool, member_did_str).await {
                                     Ok(should_send) => {
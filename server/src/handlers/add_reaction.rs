@@ -4,7 +4,9 @@ use std::sync::Arc;
 use tracing::{error, info};
 
 use crate::{
+    actors::{ActorRegistry, ConvoEvent, ConvoMessage},
     auth::AuthUser,
+    federation::{RemoteEphemeralEvent, RemoteNodeRegistry},
     generated::blue::catbird::mls::add_reaction::{Input, Output, OutputData, NSID},
     realtime::{SseState, StreamEvent},
     db,
@@ -14,10 +16,12 @@ use crate::{
 
 /// Add a reaction to a message
 /// POST /xrpc/blue.catbird.mls.addReaction
-#[tracing::instrument(skip(pool, sse_state, auth_user))]
+#[tracing::instrument(skip(pool, sse_state, actor_registry, remote_node_registry, auth_user))]
 pub async fn add_reaction(
     State(pool): State<DbPool>,
     State(sse_state): State<Arc<SseState>>,
+    State(actor_registry): State<Arc<ActorRegistry>>,
+    State(remote_node_registry): State<Option<Arc<RemoteNodeRegistry>>>,
     auth_user: AuthUser,
     Json(input): Json<Input>,
 ) -> Result<Json<Output>, StatusCode> {
@@ -125,6 +129,38 @@ pub async fn add_reaction(
         // Don't fail the request, reaction was still saved
     }
 
+    // Also publish to subscribeConvo long-poll waiters instead of dropping
+    // the event on the floor for clients not using SSE.
+    match actor_registry.get_or_spawn(&input.convo_id).await {
+        Ok(actor_ref) => {
+            let _ = actor_ref.cast(ConvoMessage::Notify(ConvoEvent::Reaction {
+                message_id: input.message_id.clone(),
+                did: user_did.clone(),
+                reaction: input.reaction.clone(),
+                action: "add".to_string(),
+            }));
+        }
+        Err(e) => error!("Failed to get conversation actor for notify: {}", e),
+    }
+
+    // Forward to members hosted on a different delivery service. Best-effort:
+    // failures here never affect the local success response.
+    if let Some(registry) = remote_node_registry {
+        if let Ok(members) = db::list_members(&pool, &input.convo_id).await {
+            registry.broadcast(
+                input.convo_id.clone(),
+                members.into_iter().map(|m| m.member_did).collect(),
+                RemoteEphemeralEvent::Reaction {
+                    message_id: input.message_id.clone(),
+                    did: user_did.clone(),
+                    reaction: input.reaction.clone(),
+                    action: "add".to_string(),
+                },
+            );
+        }
+    }
+
+    crate::metrics::record_reaction("add");
     info!("Reaction added successfully");
 
     Ok(Json(Output::from(OutputData {
@@ -0,0 +1,507 @@
+use axum::{extract::State, http::StatusCode, Json};
+use base64::Engine;
+use chrono::Utc;
+use openmls::messages::group_info::VerifiableGroupInfo;
+use openmls::prelude::MlsMessageIn;
+use ractor::ActorRef;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, sync::Arc};
+use tls_codec::Deserialize as TlsDeserialize;
+use tracing::{error, info, warn};
+
+use crate::{
+    actors::{ActorRegistry, ConvoEvent, ConvoMessage},
+    auth::AuthUser,
+    db,
+    federation::{RemoteEphemeralEvent, RemoteNodeRegistry},
+    generated_types::MessageView,
+    group_info::{get_group_info, store_group_info, MAX_GROUP_INFO_SIZE, MIN_GROUP_INFO_SIZE},
+    realtime::{SseState, StreamEvent},
+    sqlx_atrium::chrono_to_datetime,
+    storage::DbPool,
+};
+
+use super::get_messages::MessageSelector;
+
+const NSID: &str = "blue.catbird.mls.batch";
+
+/// Maximum sub-operations accepted in a single batch, so one request can't
+/// turn a single round-trip into an unbounded amount of server-side work.
+const MAX_BATCH_OPS: usize = 50;
+
+/// A single sub-operation in a batch request. Mirrors the standalone
+/// `addReaction`/`removeReaction`/`sendTypingIndicator`/`updateGroupInfo`/
+/// `getMessages` inputs, tagged so they can share one array.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum BatchOp {
+    AddReaction {
+        convo_id: String,
+        message_id: String,
+        reaction: String,
+    },
+    RemoveReaction {
+        convo_id: String,
+        message_id: String,
+        reaction: String,
+    },
+    SendTyping {
+        convo_id: String,
+        is_typing: bool,
+    },
+    UpdateGroupInfo {
+        convo_id: String,
+        group_info: String,
+        epoch: i64,
+    },
+    GetMessages {
+        convo_id: String,
+        since_seq: Option<i64>,
+        #[serde(default)]
+        selector: MessageSelector,
+        target: Option<i64>,
+        target2: Option<i64>,
+        limit: Option<i32>,
+    },
+}
+
+impl BatchOp {
+    fn convo_id(&self) -> &str {
+        match self {
+            Self::AddReaction { convo_id, .. }
+            | Self::RemoveReaction { convo_id, .. }
+            | Self::SendTyping { convo_id, .. }
+            | Self::UpdateGroupInfo { convo_id, .. }
+            | Self::GetMessages { convo_id, .. } => convo_id,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BatchInput {
+    pub ops: Vec<BatchOp>,
+}
+
+/// Outcome of a single sub-operation. `result` carries the same JSON shape
+/// the standalone endpoint would return; `error` is set instead when
+/// `success` is `false`, so one failing op never discards the others.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BatchOpResult {
+    pub success: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl BatchOpResult {
+    fn ok(result: serde_json::Value) -> Self {
+        Self {
+            success: true,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    fn err(status: StatusCode) -> Self {
+        Self {
+            success: false,
+            result: None,
+            error: Some(status.canonical_reason().unwrap_or("error").to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchOutput {
+    pub results: Vec<BatchOpResult>,
+}
+
+/// K2V-batch-style endpoint coalescing reaction/typing/group-info/message
+/// operations into one round-trip.
+/// POST /xrpc/blue.catbird.mls.batch
+///
+/// Membership is checked once per distinct `convoId` in the batch - not once
+/// per op - and, where the actor system has a conversation actor, the same
+/// `get_or_spawn` handle is reused for every op against that conversation.
+/// Ops run in array order; a failing op (bad input, not a member, conflict)
+/// gets its own error entry instead of failing the whole batch.
+#[tracing::instrument(skip(pool, sse_state, actor_registry, remote_node_registry, auth_user, input))]
+pub async fn batch(
+    State(pool): State<DbPool>,
+    State(sse_state): State<Arc<SseState>>,
+    State(actor_registry): State<Arc<ActorRegistry>>,
+    State(remote_node_registry): State<Option<Arc<RemoteNodeRegistry>>>,
+    auth_user: AuthUser,
+    Json(input): Json<BatchInput>,
+) -> Result<Json<BatchOutput>, StatusCode> {
+    if let Err(_e) = crate::auth::enforce_standard(&auth_user.claims, NSID) {
+        error!("❌ [batch] Unauthorized - failed auth check");
+        return Err(StatusCode::UNAUTHORIZED);
+    }
+
+    if input.ops.is_empty() {
+        warn!("Empty batch request");
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if input.ops.len() > MAX_BATCH_OPS {
+        warn!("Batch request exceeds max of {} ops", MAX_BATCH_OPS);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let user_did = auth_user.did.clone();
+    info!(
+        "batch: user={}, ops={}",
+        crate::crypto::redact_for_log(&user_did),
+        input.ops.len()
+    );
+
+    // Membership is per-conversation, not per-op: resolve it once for every
+    // distinct convoId in the batch and reuse the answer for each op against
+    // that conversation.
+    let mut membership: HashMap<String, bool> = HashMap::new();
+    for op in &input.ops {
+        let convo_id = op.convo_id();
+        if membership.contains_key(convo_id) {
+            continue;
+        }
+        let is_member = db::is_member(&pool, &user_did, convo_id)
+            .await
+            .map_err(|e| {
+                error!("Failed to check membership: {:?}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+        membership.insert(convo_id.to_string(), is_member);
+    }
+
+    // Likewise, fetch one actor handle per conversation the batch touches,
+    // regardless of how many ops target it.
+    let mut actor_refs: HashMap<String, ActorRef<ConvoMessage>> = HashMap::new();
+    for (convo_id, is_member) in &membership {
+        if !is_member {
+            continue;
+        }
+        match actor_registry.get_or_spawn(convo_id).await {
+            Ok(actor_ref) => {
+                actor_refs.insert(convo_id.clone(), actor_ref);
+            }
+            Err(e) => error!("Failed to get conversation actor for notify: {}", e),
+        }
+    }
+
+    let mut results = Vec::with_capacity(input.ops.len());
+    for op in input.ops {
+        let convo_id = op.convo_id().to_string();
+        if !membership.get(&convo_id).copied().unwrap_or(false) {
+            results.push(BatchOpResult::err(StatusCode::FORBIDDEN));
+            continue;
+        }
+
+        let outcome = run_op(
+            &pool,
+            &sse_state,
+            actor_refs.get(&convo_id),
+            remote_node_registry.as_ref(),
+            &user_did,
+            op,
+        )
+        .await;
+
+        results.push(match outcome {
+            Ok(value) => BatchOpResult::ok(value),
+            Err(status) => BatchOpResult::err(status),
+        });
+    }
+
+    Ok(Json(BatchOutput { results }))
+}
+
+/// Runs a single already-membership-checked op, reusing the caller's
+/// pre-resolved actor handle instead of fetching its own.
+async fn run_op(
+    pool: &DbPool,
+    sse_state: &Arc<SseState>,
+    actor_ref: Option<&ActorRef<ConvoMessage>>,
+    remote_node_registry: Option<&Arc<RemoteNodeRegistry>>,
+    user_did: &str,
+    op: BatchOp,
+) -> Result<serde_json::Value, StatusCode> {
+    match op {
+        BatchOp::AddReaction {
+            convo_id,
+            message_id,
+            reaction,
+        } => {
+            if reaction.is_empty() || reaction.len() > 16 {
+                return Err(StatusCode::BAD_REQUEST);
+            }
+
+            let message_exists = db::message_exists(pool, &convo_id, &message_id)
+                .await
+                .map_err(|e| {
+                    error!("Failed to check message existence: {:?}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            if !message_exists {
+                return Err(StatusCode::NOT_FOUND);
+            }
+
+            let now = Utc::now();
+            let inserted = db::add_reaction(pool, &convo_id, &message_id, user_did, &reaction, now)
+                .await
+                .map_err(|e| {
+                    error!("Failed to add reaction: {:?}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            if !inserted {
+                return Err(StatusCode::CONFLICT);
+            }
+
+            emit_reaction_event(pool, sse_state, actor_ref, &convo_id, &message_id, user_did, &reaction, "add").await;
+            broadcast_remote(pool, remote_node_registry, &convo_id, RemoteEphemeralEvent::Reaction {
+                message_id: message_id.clone(),
+                did: user_did.to_string(),
+                reaction: reaction.clone(),
+                action: "add".to_string(),
+            })
+            .await;
+
+            Ok(serde_json::json!({
+                "success": true,
+                "reactedAt": chrono_to_datetime(now),
+            }))
+        }
+        BatchOp::RemoveReaction {
+            convo_id,
+            message_id,
+            reaction,
+        } => {
+            let deleted = db::remove_reaction(pool, &convo_id, &message_id, user_did, &reaction)
+                .await
+                .map_err(|e| {
+                    error!("Failed to remove reaction: {:?}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+            if !deleted {
+                return Err(StatusCode::NOT_FOUND);
+            }
+
+            emit_reaction_event(pool, sse_state, actor_ref, &convo_id, &message_id, user_did, &reaction, "remove").await;
+            broadcast_remote(pool, remote_node_registry, &convo_id, RemoteEphemeralEvent::Reaction {
+                message_id: message_id.clone(),
+                did: user_did.to_string(),
+                reaction: reaction.clone(),
+                action: "remove".to_string(),
+            })
+            .await;
+
+            Ok(serde_json::json!({ "success": true }))
+        }
+        BatchOp::SendTyping { convo_id, is_typing } => {
+            let cursor = sse_state.cursor_gen.next(&convo_id, "typingEvent").await;
+            let event = StreamEvent::TypingEvent {
+                cursor,
+                convo_id: convo_id.clone(),
+                did: user_did.to_string(),
+                is_typing,
+            };
+            if let Err(e) = sse_state.emit(&convo_id, event).await {
+                error!("Failed to emit typing event: {}", e);
+            }
+            if let Some(actor_ref) = actor_ref {
+                let _ = actor_ref.cast(ConvoMessage::Notify(ConvoEvent::Typing {
+                    did: user_did.to_string(),
+                    is_typing,
+                }));
+            }
+            broadcast_remote(pool, remote_node_registry, &convo_id, RemoteEphemeralEvent::Typing {
+                did: user_did.to_string(),
+                is_typing,
+            })
+            .await;
+            crate::metrics::record_typing_indicator();
+
+            Ok(serde_json::json!({ "success": true }))
+        }
+        BatchOp::UpdateGroupInfo {
+            convo_id,
+            group_info,
+            epoch,
+        } => {
+            let group_info_bytes = base64::engine::general_purpose::STANDARD
+                .decode(&group_info)
+                .map_err(|e| {
+                    error!(convo_id = %convo_id, error = %e, "Invalid base64 in GroupInfo");
+                    crate::metrics::record_group_info_update_rejected("invalid_base64");
+                    StatusCode::BAD_REQUEST
+                })?;
+
+            if group_info_bytes.len() < MIN_GROUP_INFO_SIZE {
+                error!(convo_id = %convo_id, size = group_info_bytes.len(), "GroupInfo too small");
+                crate::metrics::record_group_info_update_rejected("size_too_small");
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            if group_info_bytes.len() > MAX_GROUP_INFO_SIZE {
+                error!(convo_id = %convo_id, size = group_info_bytes.len(), "GroupInfo too large");
+                crate::metrics::record_group_info_update_rejected("size_too_large");
+                return Err(StatusCode::BAD_REQUEST);
+            }
+
+            let valid = MlsMessageIn::tls_deserialize(&mut group_info_bytes.as_slice()).is_ok()
+                || VerifiableGroupInfo::tls_deserialize(&mut group_info_bytes.as_slice()).is_ok();
+            if !valid {
+                error!(convo_id = %convo_id, "Invalid MLS GroupInfo structure");
+                crate::metrics::record_group_info_update_rejected("invalid_structure");
+                return Err(StatusCode::BAD_REQUEST);
+            }
+
+            if let Ok(Some((_, existing_epoch, _))) = get_group_info(pool, &convo_id).await {
+                if epoch as i32 <= existing_epoch {
+                    warn!(
+                        convo_id = %convo_id,
+                        new_epoch = epoch,
+                        existing_epoch = existing_epoch,
+                        "Rejecting GroupInfo with non-increasing epoch"
+                    );
+                    crate::metrics::record_group_info_update_rejected("epoch_not_increasing");
+                    return Err(StatusCode::CONFLICT);
+                }
+            }
+
+            store_group_info(pool, &convo_id, &group_info_bytes, epoch as i32)
+                .await
+                .map_err(|e| {
+                    error!(convo_id = %convo_id, error = %e, "Failed to store GroupInfo");
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?;
+
+            crate::metrics::record_group_info_update_accepted();
+            Ok(serde_json::json!({ "updated": true }))
+        }
+        BatchOp::GetMessages {
+            convo_id,
+            since_seq,
+            selector,
+            target,
+            target2,
+            limit,
+        } => {
+            let limit = limit.unwrap_or(50).min(100).max(1);
+            let pivot = target.or(since_seq);
+            let messages = match selector {
+                MessageSelector::Latest => match pivot {
+                    Some(since_seq) => db::list_messages_since_seq(pool, &convo_id, since_seq, limit as i64).await,
+                    None => db::list_messages(pool, &convo_id, None, limit as i64).await,
+                },
+                MessageSelector::Before => {
+                    let target = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+                    db::list_messages_before_seq(pool, &convo_id, target, limit as i64).await
+                }
+                MessageSelector::After => {
+                    let target = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+                    db::list_messages_since_seq(pool, &convo_id, target, limit as i64).await
+                }
+                MessageSelector::Around => {
+                    let target = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+                    db::list_messages_around_seq(pool, &convo_id, target, limit as i64).await
+                }
+                MessageSelector::Between => {
+                    let start = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+                    let end = target2.ok_or(StatusCode::BAD_REQUEST)?;
+                    if start >= end {
+                        return Err(StatusCode::BAD_REQUEST);
+                    }
+                    db::list_messages_between_seq(pool, &convo_id, start, end, limit as i64).await
+                }
+            }
+            .map_err(|e| {
+                error!("Failed to fetch messages for batch getMessages: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?;
+
+            let message_views: Vec<MessageView> = messages
+                .into_iter()
+                .map(|m| MessageView {
+                    id: m.id,
+                    convo_id: m.convo_id,
+                    ciphertext: m.ciphertext,
+                    epoch: m.epoch,
+                    seq: m.seq,
+                    created_at: m.created_at,
+                })
+                .collect();
+            let last_seq = message_views.last().map(|m| m.seq);
+
+            let mut result = serde_json::json!({ "messages": message_views });
+            if let Some(seq) = last_seq {
+                result["lastSeq"] = serde_json::json!(seq);
+            }
+            Ok(result)
+        }
+    }
+}
+
+/// Emits a reaction event to SSE subscribers, `subscribeConvo` long-pollers,
+/// and (best-effort) the cursor-replay event log - the same three sinks
+/// `addReaction`/`removeReaction` write to standalone.
+async fn emit_reaction_event(
+    pool: &DbPool,
+    sse_state: &Arc<SseState>,
+    actor_ref: Option<&ActorRef<ConvoMessage>>,
+    convo_id: &str,
+    message_id: &str,
+    user_did: &str,
+    reaction: &str,
+    action: &str,
+) {
+    let cursor = sse_state.cursor_gen.next(convo_id, "reactionEvent").await;
+    let event = StreamEvent::ReactionEvent {
+        cursor: cursor.clone(),
+        convo_id: convo_id.to_string(),
+        message_id: message_id.to_string(),
+        did: user_did.to_string(),
+        reaction: reaction.to_string(),
+        action: action.to_string(),
+    };
+
+    if action == "add" {
+        if let Err(e) = db::store_event(pool, &cursor, convo_id, "reactionEvent", Some(message_id)).await {
+            error!("Failed to store reaction event: {:?}", e);
+        }
+    }
+
+    if let Err(e) = sse_state.emit(convo_id, event).await {
+        error!("Failed to emit reaction event: {}", e);
+    }
+
+    if let Some(actor_ref) = actor_ref {
+        let _ = actor_ref.cast(ConvoMessage::Notify(ConvoEvent::Reaction {
+            message_id: message_id.to_string(),
+            did: user_did.to_string(),
+            reaction: reaction.to_string(),
+            action: action.to_string(),
+        }));
+    }
+
+    crate::metrics::record_reaction(action);
+}
+
+/// Forwards an ephemeral event to remote delivery services, when federation
+/// fan-out is configured.
+async fn broadcast_remote(
+    pool: &DbPool,
+    remote_node_registry: Option<&Arc<RemoteNodeRegistry>>,
+    convo_id: &str,
+    event: RemoteEphemeralEvent,
+) {
+    if let Some(registry) = remote_node_registry {
+        if let Ok(members) = db::list_members(pool, convo_id).await {
+            registry.broadcast(
+                convo_id.to_string(),
+                members.into_iter().map(|m| m.member_did).collect(),
+                event,
+            );
+        }
+    }
+}
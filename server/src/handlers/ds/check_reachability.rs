@@ -0,0 +1,119 @@
+use axum::{
+    extract::{Query, State},
+    Json,
+};
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+use tracing::debug;
+
+use crate::{
+    auth::AuthUser,
+    federation::{DsResolver, FederationError, CAPABILITIES, PROTOCOL_VERSION},
+    identity::canonical_did,
+    storage::DbPool,
+};
+
+const NSID: &str = "blue.catbird.mls.ds.checkReachability";
+
+#[derive(Debug, Deserialize)]
+pub struct CheckReachabilityParams {
+    pub convo_id: String,
+    pub sequencer_did: String,
+}
+
+/// GET /xrpc/blue.catbird.mls.ds.checkReachability
+///
+/// Reports whether *this* DS can reach `sequencer_did`'s `healthCheck`
+/// endpoint. Used as a quorum vote by
+/// [`crate::handlers::mls_chat::request_failover`]: before a candidate DS
+/// assumes the sequencer role it asks every other participant DS this
+/// question, so one DS's partition-local view of "unreachable" can't
+/// unilaterally trigger a takeover.
+///
+/// Also carries this DS's own `protocolVersion`/`capabilities`, piggybacked
+/// on the vote response so the candidate can negotiate a federation
+/// protocol version with every voter without a second round trip to each
+/// one's `healthCheck`.
+#[tracing::instrument(skip(pool, resolver, auth_user, query))]
+pub async fn check_reachability(
+    State(pool): State<DbPool>,
+    State(resolver): State<Arc<dyn DsResolver>>,
+    auth_user: AuthUser,
+    Query(query): Query<CheckReachabilityParams>,
+) -> Result<Json<serde_json::Value>, FederationError> {
+    let security =
+        super::deliver_message::enforce_ds_request_security(&pool, &auth_user, NSID, None).await?;
+    let requester_ds = security.requester_ds.clone();
+
+    let self_did = canonical_did(
+        &std::env::var("SERVICE_DID").unwrap_or_else(|_| "did:web:mls.catbird.blue".to_string()),
+    )
+    .to_string();
+
+    // Only a DS that actually has members in this conversation may ask us
+    // to probe a sequencer on its behalf.
+    let caller_is_member_ds: bool = sqlx::query_scalar(
+        "SELECT EXISTS(SELECT 1 FROM members WHERE convo_id = $1 AND left_at IS NULL \
+           AND COALESCE(split_part(ds_did, '#', 1), $2) = $3)",
+    )
+    .bind(&query.convo_id)
+    .bind(&self_did)
+    .bind(&requester_ds)
+    .fetch_one(&pool)
+    .await
+    .map_err(FederationError::Database)?;
+
+    if !caller_is_member_ds {
+        return Err(FederationError::AuthFailed {
+            reason: format!(
+                "{requester_ds} is not a participant DS for {}",
+                query.convo_id
+            ),
+        });
+    }
+
+    let endpoint = match resolver.resolve(&query.sequencer_did).await {
+        Ok(ep) => ep.endpoint,
+        Err(e) => {
+            debug!(
+                convo_id = %crate::crypto::redact_for_log(&query.convo_id),
+                sequencer = %crate::crypto::redact_for_log(&query.sequencer_did),
+                error = %e,
+                "Cannot resolve sequencer endpoint for reachability vote, voting unreachable"
+            );
+            return Ok(Json(serde_json::json!({
+                "reachable": false,
+                "protocolVersion": PROTOCOL_VERSION,
+                "capabilities": CAPABILITIES,
+            })));
+        }
+    };
+
+    let health_url = format!(
+        "{}/xrpc/blue.catbird.mls.ds.healthCheck",
+        endpoint.trim_end_matches('/')
+    );
+
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(5))
+        .build()
+        .unwrap_or_default();
+
+    let reachable = matches!(
+        client.get(&health_url).send().await,
+        Ok(resp) if resp.status().is_success()
+    );
+
+    debug!(
+        convo_id = %crate::crypto::redact_for_log(&query.convo_id),
+        sequencer = %crate::crypto::redact_for_log(&query.sequencer_did),
+        reachable,
+        "Cast reachability vote"
+    );
+
+    Ok(Json(serde_json::json!({
+        "reachable": reachable,
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": CAPABILITIES,
+    })))
+}
@@ -0,0 +1,181 @@
+use axum::{extract::State, Json};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use tracing::{error, info};
+
+use crate::{
+    auth::AuthUser,
+    blob_storage::BlobStorage,
+    federation::FederationError,
+    storage::DbPool,
+};
+
+use super::deliver_message::{enforce_ds_request_security, record_ds_outcome};
+
+const NSID: &str = "blue.catbird.mls.deliverMessage";
+
+/// Body POSTed by a peer DS on behalf of the recipients it resolved us as
+/// home to. Mirrors [`crate::handlers::messages::FederatedBlobMessage`], the
+/// shape the sending side serializes.
+#[derive(Debug, Deserialize)]
+pub struct DeliverBlobMessageBody {
+    pub message_id: String,
+    pub convo_id: String,
+    pub sender_did: String,
+    /// Base64-encoded, same encoding as the v1 `storeMessage` request.
+    pub encrypted_data: String,
+    pub recipients: Vec<String>,
+    pub metadata: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliverBlobMessageResponse {
+    pub accepted: bool,
+}
+
+/// POST /xrpc/blue.catbird.mls.deliverMessage
+///
+/// Receiving side of federated store-and-forward blob delivery: a peer DS
+/// resolved some of its senders' recipients to be home to this DS (via
+/// `DsResolver`) and forwards the already-encrypted blob here instead of us
+/// ever storing it for them. Storage and fanout mirror
+/// `handlers::messages::store_message`'s local path; the insert on `messages`
+/// is idempotent on `message_id` so a retried delivery after a dropped ACK
+/// doesn't double-store the blob or double-advance recipient cursors.
+#[tracing::instrument(skip(pool, blob_storage, push_fanout, auth_user, body))]
+pub async fn deliver_blob_message(
+    State(pool): State<DbPool>,
+    State(blob_storage): State<Arc<BlobStorage>>,
+    State(push_fanout): State<Option<Arc<crate::push::PushFanout>>>,
+    auth_user: AuthUser,
+    Json(body): Json<DeliverBlobMessageBody>,
+) -> Result<Json<DeliverBlobMessageResponse>, FederationError> {
+    let security = enforce_ds_request_security(&pool, &auth_user, NSID, None).await?;
+    let requester_ds = security.requester_ds.clone();
+
+    let result: Result<Json<DeliverBlobMessageResponse>, FederationError> = async {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+        // Everything below runs in one transaction so a crash between the
+        // `messages` insert and the `message_recipients`/cursor inserts can't
+        // leave `already_stored` true with no recipients ever recorded - the
+        // previous split (messages committed on its own connection, then a
+        // separate recipient transaction) made exactly that failure
+        // permanent, since every retry would short-circuit on the check
+        // below before reaching the recipient inserts.
+        let mut tx = pool.begin().await.map_err(FederationError::Database)?;
+
+        let already_stored =
+            sqlx::query_scalar::<_, bool>("SELECT EXISTS(SELECT 1 FROM messages WHERE id = $1)")
+                .bind(&body.message_id)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(FederationError::Database)?;
+
+        if already_stored {
+            info!(
+                message_id = %body.message_id,
+                requester_ds,
+                "Duplicate federated blob delivery, skipping re-store"
+            );
+            return Ok(Json(DeliverBlobMessageResponse { accepted: true }));
+        }
+
+        let encrypted_bytes = BASE64.decode(&body.encrypted_data).map_err(|e| {
+            FederationError::AuthFailed {
+                reason: format!("Invalid base64 encrypted_data: {e}"),
+            }
+        })?;
+        let encrypted_bytes_for_push = encrypted_bytes.clone();
+
+        let blob_key = blob_storage
+            .store_blob(&body.message_id, encrypted_bytes)
+            .await
+            .map_err(|e| FederationError::ConfigError {
+                reason: format!("blob storage write failed: {e}"),
+            })?;
+
+        let created_at = chrono::Utc::now();
+
+        sqlx::query(
+            r#"
+            INSERT INTO messages (id, convo_id, sender_did, blob_key, created_at, metadata)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (id) DO NOTHING
+            "#,
+        )
+        .bind(&body.message_id)
+        .bind(&body.convo_id)
+        .bind(&body.sender_did)
+        .bind(&blob_key)
+        .bind(created_at)
+        .bind(&body.metadata)
+        .execute(&mut *tx)
+        .await
+        .map_err(FederationError::Database)?;
+
+        for recipient_did in &body.recipients {
+            let recipient_seq: i64 = sqlx::query_scalar(
+                r#"
+                INSERT INTO recipient_sync_cursors (recipient_did, next_seq)
+                VALUES ($1, 1)
+                ON CONFLICT (recipient_did) DO UPDATE
+                SET next_seq = recipient_sync_cursors.next_seq + 1
+                RETURNING next_seq
+                "#,
+            )
+            .bind(recipient_did)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(FederationError::Database)?;
+
+            // Dedup on (message_id, recipient_did): if this delivery is ever
+            // retried after the cursor advance above but before commit, the
+            // recipient row itself still lands exactly once.
+            sqlx::query(
+                r#"
+                INSERT INTO message_recipients (message_id, recipient_did, delivered, recipient_seq)
+                VALUES ($1, $2, false, $3)
+                ON CONFLICT (message_id, recipient_did) DO NOTHING
+                "#,
+            )
+            .bind(&body.message_id)
+            .bind(recipient_did)
+            .bind(recipient_seq)
+            .execute(&mut *tx)
+            .await
+            .map_err(FederationError::Database)?;
+        }
+        tx.commit().await.map_err(FederationError::Database)?;
+
+        info!(
+            message_id = %body.message_id,
+            sender = %body.sender_did,
+            convo_id = %body.convo_id,
+            requester_ds,
+            recipients = body.recipients.len(),
+            "Accepted federated blob message"
+        );
+
+        if let Some(push_fanout) = push_fanout.as_ref() {
+            if !body.recipients.is_empty() {
+                push_fanout.notify(
+                    pool.clone(),
+                    body.convo_id.clone(),
+                    body.message_id.clone(),
+                    body.recipients.clone(),
+                    Some(encrypted_bytes_for_push),
+                );
+            }
+        }
+
+        Ok(Json(DeliverBlobMessageResponse { accepted: true }))
+    }
+    .await;
+
+    if let Err(ref e) = result {
+        error!(message_id = %body.message_id, requester_ds, error = %e, "Failed to accept federated blob message");
+    }
+    record_ds_outcome(&pool, &requester_ds, result.is_ok()).await;
+    result
+}
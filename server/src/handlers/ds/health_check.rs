@@ -2,7 +2,7 @@ use axum::Json;
 use serde_json::json;
 use tracing::debug;
 
-use crate::federation::FederationError;
+use crate::federation::{FederationError, CAPABILITIES, PROTOCOL_VERSION};
 
 /// GET /xrpc/blue.catbird.mls.ds.healthCheck
 ///
@@ -21,7 +21,13 @@ pub async fn health_check() -> Result<Json<serde_json::Value>, FederationError>
     Ok(Json(json!({
         "did": did,
         "version": "1.0.0",
-        "uptime": uptime
+        "uptime": uptime,
+        // Federation handshake: lets peers negotiate down to a mutually
+        // understood version/capability set before a takeover or transfer
+        // (see `handlers::mls_chat::request_failover`) instead of assuming
+        // every DS speaks the same wire protocol.
+        "protocolVersion": PROTOCOL_VERSION,
+        "capabilities": CAPABILITIES,
     })))
 }
 
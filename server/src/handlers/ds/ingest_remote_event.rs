@@ -0,0 +1,176 @@
+use axum::{extract::State, Json};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+use tracing::warn;
+
+use super::deliver_message::{enforce_ds_request_security, record_ds_outcome};
+use crate::{
+    actors::{ActorRegistry, ConvoEvent, ConvoMessage},
+    auth::AuthUser,
+    db,
+    federation::{DsResolver, FederationError, IngestRemoteEventBody, RemoteEphemeralEvent},
+    identity::canonical_did,
+    realtime::SseState,
+    storage::DbPool,
+};
+
+const NSID: &str = "blue.catbird.mls.ds.ingestRemoteEvent";
+
+/// How long an idempotency key is remembered before it can be reused.
+/// Generously longer than any plausible retry window for a 3-attempt,
+/// seconds-scale backoff.
+const DEDUP_TTL: Duration = Duration::from_secs(300);
+
+static SEEN_KEYS: Lazy<Mutex<HashMap<String, Instant>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// Returns `true` if `key` has already been seen within [`DEDUP_TTL`] and
+/// records it if not. Also opportunistically sweeps expired entries so the
+/// map doesn't grow unbounded.
+fn already_processed(key: &str) -> bool {
+    let mut seen = SEEN_KEYS.lock().expect("dedup cache lock poisoned");
+    let now = Instant::now();
+    seen.retain(|_, seen_at| now.duration_since(*seen_at) < DEDUP_TTL);
+
+    if seen.contains_key(key) {
+        true
+    } else {
+        seen.insert(key.to_string(), now);
+        false
+    }
+}
+
+/// POST /xrpc/blue.catbird.mls.ds.ingestRemoteEvent
+///
+/// Accept an ephemeral reaction/typing event forwarded by another DS on
+/// behalf of one of its local members, and fan it out to this DS's local
+/// subscribers (SSE + `subscribeConvo` long-pollers). The forwarding DS must
+/// actually be the home node of the event's actor DID - an `A` forwarding an
+/// event as if it were from a user homed on `B` is rejected.
+#[tracing::instrument(skip(pool, sse_state, actor_registry, auth_user, body))]
+pub async fn ingest_remote_event(
+    State(pool): State<DbPool>,
+    State(sse_state): State<Arc<SseState>>,
+    State(actor_registry): State<Arc<ActorRegistry>>,
+    State(ds_resolver): State<Arc<dyn DsResolver>>,
+    auth_user: AuthUser,
+    Json(body): Json<IngestRemoteEventBody>,
+) -> Result<Json<serde_json::Value>, FederationError> {
+    let security = enforce_ds_request_security(&pool, &auth_user, NSID, None).await?;
+    let requester_ds = security.requester_ds.clone();
+
+    let result: Result<Json<serde_json::Value>, FederationError> = async {
+        let actor_did = match &body.event {
+            RemoteEphemeralEvent::Reaction { did, .. } => did.as_str(),
+            RemoteEphemeralEvent::Typing { did, .. } => did.as_str(),
+        };
+
+        if !db::is_member(&pool, actor_did, &body.convo_id)
+            .await
+            .map_err(FederationError::Database)?
+        {
+            return Err(FederationError::RecipientNotFound {
+                did: actor_did.to_string(),
+            });
+        }
+
+        // The forwarding DS must actually be the actor's home node - without
+        // this, any federated peer could forge events on behalf of users it
+        // doesn't host. Uses the shared resolver (one client, not one per
+        // request) the same way the producer side (`RemoteNodeRegistry`) does.
+        let resolved = ds_resolver.resolve(actor_did).await?;
+
+        if canonical_did(&resolved.did) != requester_ds {
+            return Err(FederationError::AuthFailed {
+                reason: format!(
+                    "DS {} is not the home node of actor {}",
+                    requester_ds, actor_did
+                ),
+            });
+        }
+
+        if already_processed(&body.idempotency_key) {
+            return Ok(Json(json!({ "accepted": true, "duplicate": true })));
+        }
+
+        let convo_event = match &body.event {
+            RemoteEphemeralEvent::Reaction {
+                message_id,
+                did,
+                reaction,
+                action,
+            } => ConvoEvent::Reaction {
+                message_id: message_id.clone(),
+                did: did.clone(),
+                reaction: reaction.clone(),
+                action: action.clone(),
+            },
+            RemoteEphemeralEvent::Typing { did, is_typing } => ConvoEvent::Typing {
+                did: did.clone(),
+                is_typing: *is_typing,
+            },
+        };
+
+        match actor_registry.get_or_spawn(&body.convo_id).await {
+            Ok(actor_ref) => {
+                let _ = actor_ref.cast(ConvoMessage::Notify(convo_event.clone()));
+            }
+            Err(e) => warn!(convo_id = %body.convo_id, error = %e, "Failed to notify local subscribers of remote event"),
+        }
+
+        if let Some(stream_event) = to_stream_event(&sse_state, &body.convo_id, &convo_event).await
+        {
+            if let Err(e) = sse_state.emit(&body.convo_id, stream_event).await {
+                warn!(convo_id = %body.convo_id, error = %e, "Failed to emit SSE event for remote event");
+            }
+        }
+
+        Ok(Json(json!({ "accepted": true })))
+    }
+    .await;
+
+    record_ds_outcome(&pool, &requester_ds, result.is_ok()).await;
+    result
+}
+
+/// Build the equivalent SSE [`StreamEvent`](crate::realtime::StreamEvent) for
+/// a locally-fanned-out remote event, reusing the same cursor sequence SSE
+/// subscribers already expect from `addReaction`/`sendTypingIndicator`.
+async fn to_stream_event(
+    sse_state: &SseState,
+    convo_id: &str,
+    event: &ConvoEvent,
+) -> Option<crate::realtime::StreamEvent> {
+    match event {
+        ConvoEvent::Reaction {
+            message_id,
+            did,
+            reaction,
+            action,
+        } => {
+            let cursor = sse_state.cursor_gen.next(convo_id, "reactionEvent").await;
+            Some(crate::realtime::StreamEvent::ReactionEvent {
+                cursor,
+                convo_id: convo_id.to_string(),
+                message_id: message_id.clone(),
+                did: did.clone(),
+                reaction: reaction.clone(),
+                action: action.clone(),
+            })
+        }
+        ConvoEvent::Typing { did, is_typing } => {
+            let cursor = sse_state.cursor_gen.next(convo_id, "typingEvent").await;
+            Some(crate::realtime::StreamEvent::TypingEvent {
+                cursor,
+                convo_id: convo_id.to_string(),
+                did: did.clone(),
+                is_typing: *is_typing,
+            })
+        }
+        ConvoEvent::Message { .. } => None,
+    }
+}
@@ -1,13 +1,19 @@
+pub mod check_reachability;
+pub mod deliver_blob_message;
 pub mod deliver_message;
 pub mod deliver_welcome;
 pub mod fetch_key_package;
 pub mod health_check;
+pub mod ingest_remote_event;
 pub mod submit_commit;
 pub mod transfer_sequencer;
 
+pub use check_reachability::check_reachability;
+pub use deliver_blob_message::deliver_blob_message;
 pub use deliver_message::deliver_message;
 pub use deliver_welcome::deliver_welcome;
 pub use fetch_key_package::fetch_key_package;
 pub use health_check::health_check;
+pub use ingest_remote_event::ingest_remote_event;
 pub use submit_commit::submit_commit;
 pub use transfer_sequencer::transfer_sequencer;
@@ -1,22 +1,105 @@
-use axum::{extract::State, Json};
+use axum::{extract::State, http::HeaderMap, Json};
+use once_cell::sync::Lazy;
 use serde_json::json;
-use tracing::debug;
+use tracing::{debug, warn};
 
 use crate::{
     auth::AuthUser,
-    federation::{FederationError, SequencerTransfer},
+    federation::{FederationError, SequencerTransfer, SignedHeaders},
     storage::DbPool,
 };
 
 const NSID: &str = "blue.catbird.mls.ds.transferSequencer";
 
+/// Shared across requests so its DID-resolution caches (including the
+/// negative cache) actually get hit, rather than starting empty on every
+/// call the way a per-request `AuthMiddleware::new()` would.
+static SIGNATURE_AUTH_MIDDLEWARE: Lazy<crate::auth::AuthMiddleware> =
+    Lazy::new(crate::auth::AuthMiddleware::new);
+
+/// Verify the RFC-9421-style signature headers attached by
+/// [`crate::federation::RequestSigner`] (see
+/// `handlers::mls_chat::request_failover::broadcast_sequencer_change`,
+/// which signs every failover broadcast through the outbound queue).
+///
+/// Absent headers are rejected by default - an unsigned transfer request
+/// would let any DS that can reach this endpoint claim a conversation's
+/// sequencer role with no replay protection at all. Set
+/// `ALLOW_UNSIGNED_DS_REQUESTS=true` to tolerate peers that haven't rolled
+/// out request signing yet; this should only be used for a bounded rollout
+/// window, never left on permanently. Present-but-invalid headers are always
+/// rejected regardless of this flag.
+async fn verify_request_signature(
+    headers: &HeaderMap,
+    requester_ds: &str,
+    body: &str,
+) -> Result<(), FederationError> {
+    let get = |name: &str| headers.get(name).and_then(|v| v.to_str().ok());
+    let (content_digest, date, signature_input, signature) = match (
+        get("content-digest"),
+        get("date"),
+        get("signature-input"),
+        get("signature"),
+    ) {
+        (Some(cd), Some(d), Some(si), Some(s)) => (cd, d, si, s),
+        _ => {
+            if allow_unsigned_ds_requests() {
+                warn!(requester_ds, "transferSequencer request has no signature headers, tolerating under ALLOW_UNSIGNED_DS_REQUESTS");
+                return Ok(());
+            }
+            return Err(FederationError::AuthFailed {
+                reason: format!(
+                    "transferSequencer request from {requester_ds} is missing RFC-9421 signature headers"
+                ),
+            });
+        }
+    };
+
+    let signed_headers = SignedHeaders {
+        content_digest: content_digest.to_string(),
+        date: date.to_string(),
+        signature_input: signature_input.to_string(),
+        signature: signature.to_string(),
+    };
+
+    let did_doc = SIGNATURE_AUTH_MIDDLEWARE
+        .resolve_did(requester_ds)
+        .await
+        .map_err(|e| FederationError::AuthFailed {
+            reason: format!("could not resolve signing key for {requester_ds}: {e}"),
+        })?;
+    let verifying_key = crate::auth::extract_p256_key(&did_doc).ok_or_else(|| {
+        FederationError::AuthFailed {
+            reason: format!("no P-256 key found in DID document for {requester_ds}"),
+        }
+    })?;
+
+    crate::federation::request_signing::verify_signed_request(
+        &verifying_key,
+        "POST",
+        "/xrpc/blue.catbird.mls.ds.transferSequencer",
+        body.as_bytes(),
+        &signed_headers,
+    )
+}
+
+/// Whether to tolerate a `transferSequencer` request with no RFC-9421
+/// signature headers. Defaults to `false` (strict, signatures required) -
+/// see [`verify_request_signature`].
+fn allow_unsigned_ds_requests() -> bool {
+    std::env::var("ALLOW_UNSIGNED_DS_REQUESTS")
+        .map(|v| v == "true")
+        .unwrap_or(false)
+}
+
 /// POST /xrpc/blue.catbird.mls.ds.transferSequencer
 ///
 /// Accept a sequencer role transfer from the current sequencer DS.
-#[tracing::instrument(skip(pool, auth_user, body))]
+#[tracing::instrument(skip(pool, auth_user, headers, body))]
 pub async fn transfer_sequencer(
     State(pool): State<DbPool>,
     auth_user: AuthUser,
+    headers: HeaderMap,
     body: String,
 ) -> Result<Json<serde_json::Value>, FederationError> {
     let transfer = crate::jacquard_json::from_json_body::<
@@ -33,6 +116,7 @@ pub async fn transfer_sequencer(
     let security =
         super::deliver_message::enforce_ds_request_security(&pool, &auth_user, NSID, None).await?;
     let requester_ds = security.requester_ds.clone();
+    verify_request_signature(&headers, &requester_ds, &body).await?;
     let from_ds = requester_ds.as_str();
     let current_epoch = transfer.current_epoch as i32;
 
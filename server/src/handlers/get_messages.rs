@@ -12,12 +12,57 @@ use crate::{
     storage::{is_member, DbPool},
 };
 
+/// CHATHISTORY-style message selector, modeled on the IRC `CHATHISTORY`
+/// command. Lets a client recovering from a gap (see `GapInfoResponse`)
+/// query relative to a pivot `seq` instead of only paging forward from the
+/// latest message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum MessageSelector {
+    /// Latest messages, newest activity first (the existing default).
+    Latest,
+    /// Messages with `seq < target`, newest-first, up to `limit`.
+    Before,
+    /// Messages with `seq > target`, oldest-first, up to `limit`.
+    After,
+    /// `floor(limit/2)` messages with `seq < target` plus the remainder
+    /// with `seq >= target`, merged and returned in ascending seq order.
+    Around,
+    /// Messages with `target < seq < target2`, ascending, capped at `limit`.
+    Between,
+}
+
+impl Default for MessageSelector {
+    fn default() -> Self {
+        Self::Latest
+    }
+}
+
+impl MessageSelector {
+    /// Low-cardinality label for metrics - the variant name, lowercased.
+    fn metric_label(&self) -> &'static str {
+        match self {
+            Self::Latest => "latest",
+            Self::Before => "before",
+            Self::After => "after",
+            Self::Around => "around",
+            Self::Between => "between",
+        }
+    }
+}
+
 #[derive(Debug, Deserialize)]
 pub struct GetMessagesParams {
     #[serde(rename = "convoId")]
     pub convo_id: String,
     #[serde(rename = "sinceSeq")]
     pub since_seq: Option<i64>,
+    #[serde(default)]
+    pub selector: MessageSelector,
+    /// Pivot seq for `BEFORE`/`AFTER`/`AROUND`, or the lower bound for `BETWEEN`.
+    pub target: Option<i64>,
+    /// Upper bound for `BETWEEN`; ignored by other selectors.
+    pub target2: Option<i64>,
     pub limit: Option<i32>,
 }
 
@@ -50,6 +95,7 @@ pub async fn get_messages(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    let started = std::time::Instant::now();
     let limit = params.limit.unwrap_or(50).min(100).max(1);
 
     // Check if user is a member
@@ -67,23 +113,66 @@ pub async fn get_messages(
     // Note: Reduced logging per security hardening - no convo IDs at info level
     tracing::debug!("Fetching messages from convo {}", crate::crypto::redact_for_log(&params.convo_id));
 
-    // Fetch messages using seq-based pagination if sinceSeq is provided
-    let messages = if let Some(since_seq) = params.since_seq {
-        // Get messages after a specific sequence number
-        db::list_messages_since_seq(&pool, &params.convo_id, since_seq, limit as i64)
-            .await
-            .map_err(|e| {
-                error!("Failed to fetch messages since seq {}: {}", since_seq, e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?
-    } else {
-        // Get latest messages (ordered by epoch, seq)
-        db::list_messages(&pool, &params.convo_id, None, limit as i64)
-            .await
-            .map_err(|e| {
-                error!("Failed to list messages: {}", e);
-                StatusCode::INTERNAL_SERVER_ERROR
-            })?
+    // Fetch messages according to the requested selector. `target` falls
+    // back to the legacy `sinceSeq` param so `selector=AFTER` and the old
+    // `sinceSeq`-only callers behave identically.
+    let pivot = params.target.or(params.since_seq);
+    let messages = match params.selector {
+        MessageSelector::Latest => match pivot {
+            Some(since_seq) => db::list_messages_since_seq(&pool, &params.convo_id, since_seq, limit as i64)
+                .await
+                .map_err(|e| {
+                    error!("Failed to fetch messages since seq {}: {}", since_seq, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?,
+            None => db::list_messages(&pool, &params.convo_id, None, limit as i64)
+                .await
+                .map_err(|e| {
+                    error!("Failed to list messages: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?,
+        },
+        MessageSelector::Before => {
+            let target = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+            db::list_messages_before_seq(&pool, &params.convo_id, target, limit as i64)
+                .await
+                .map_err(|e| {
+                    error!("Failed to fetch messages before seq {}: {}", target, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+        }
+        MessageSelector::After => {
+            let target = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+            db::list_messages_since_seq(&pool, &params.convo_id, target, limit as i64)
+                .await
+                .map_err(|e| {
+                    error!("Failed to fetch messages after seq {}: {}", target, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+        }
+        MessageSelector::Around => {
+            let target = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+            db::list_messages_around_seq(&pool, &params.convo_id, target, limit as i64)
+                .await
+                .map_err(|e| {
+                    error!("Failed to fetch messages around seq {}: {}", target, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+        }
+        MessageSelector::Between => {
+            let start = pivot.ok_or(StatusCode::BAD_REQUEST)?;
+            let end = params.target2.ok_or(StatusCode::BAD_REQUEST)?;
+            if start >= end {
+                warn!("Invalid BETWEEN range: {} to {}", start, end);
+                return Err(StatusCode::BAD_REQUEST);
+            }
+            db::list_messages_between_seq(&pool, &params.convo_id, start, end, limit as i64)
+                .await
+                .map_err(|e| {
+                    error!("Failed to fetch messages between {} and {}: {}", start, end, e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                })?
+        }
     };
 
     // Detect gaps in message sequence
@@ -93,6 +182,7 @@ pub async fn get_messages(
             error!("Failed to detect message gaps: {}", e);
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
+    crate::metrics::record_message_gaps(gap_info.missing_seqs.len());
 
     // Convert to view models with ciphertext
     // Note: sender field removed per security hardening - clients derive sender from decrypted MLS content
@@ -157,6 +247,8 @@ pub async fn get_messages(
     }
 
     info!("Fetched {} messages", message_views.len());
+    crate::metrics::record_messages_fetched(message_views.len());
+    crate::metrics::record_get_messages_duration(params.selector.metric_label(), started.elapsed());
 
     // Calculate lastSeq from the last message in the result
     let last_seq = message_views.last().map(|m| m.seq);
@@ -0,0 +1,76 @@
+use crate::auth::Claims;
+use crate::db::{self, DbPool, KEY_PACKAGE_REPLENISH_THRESHOLD};
+use anyhow::Context;
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use tracing::error;
+
+/// Optional override of whose inventory to report; defaults to the caller.
+#[derive(Debug, Deserialize)]
+pub struct KeyPackageCountQuery {
+    pub owner_did: Option<String>,
+}
+
+/// Response for the key-package inventory check.
+#[derive(Debug, Serialize)]
+pub struct KeyPackageCountResponse {
+    pub owner_did: String,
+    pub available: i64,
+    /// True once `available` has dropped below [`KEY_PACKAGE_REPLENISH_THRESHOLD`],
+    /// so clients know to publish fresh key packages before uploading more.
+    pub replenish_needed: bool,
+    pub threshold: i64,
+}
+
+/// Report how many unconsumed, unexpired key packages remain on file for a
+/// device, so clients can decide when to top up their prekey bundle instead
+/// of waiting to be told via a notification.
+/// GET /api/v1/key-packages/count
+pub async fn get_key_package_count(
+    claims: Claims,
+    State(db_pool): State<DbPool>,
+    Query(params): Query<KeyPackageCountQuery>,
+) -> Result<Json<KeyPackageCountResponse>, AppError> {
+    let owner_did = params.owner_did.unwrap_or_else(|| claims.sub.clone());
+
+    let available = db::count_available_key_packages(&db_pool, &owner_did)
+        .await
+        .context("Failed to count available key packages")?;
+
+    Ok(Json(KeyPackageCountResponse {
+        owner_did,
+        replenish_needed: available < KEY_PACKAGE_REPLENISH_THRESHOLD,
+        threshold: KEY_PACKAGE_REPLENISH_THRESHOLD,
+        available,
+    }))
+}
+
+/// Error type for key-package inventory handlers.
+pub struct AppError(anyhow::Error);
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        error!("Handler error: {:?}", self.0);
+        (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(serde_json::json!({
+                "error": format!("{}", self.0)
+            })),
+        )
+            .into_response()
+    }
+}
+
+impl<E> From<E> for AppError
+where
+    E: Into<anyhow::Error>,
+{
+    fn from(err: E) -> Self {
+        Self(err.into())
+    }
+}
@@ -1,19 +1,39 @@
-use crate::auth::Claims;
+use crate::auth::AuthUser;
 use crate::blob_storage::BlobStorage;
 use crate::db::DbPool;
+use crate::federation::{self, DsResolver};
 use anyhow::Context;
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::{IntoResponse, Response},
     Json,
 };
 use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::sync::Arc;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 use uuid::Uuid;
 
+/// Lexicon method the receiving DS exposes for store-and-forward delivery.
+/// Mirrors the shape of [`StoreMessageRequest`], scoped to the recipients
+/// that DS is actually home to (see `handlers::ds::deliver_blob_message`).
+const DELIVER_MESSAGE_NSID: &str = "blue.catbird.mls.deliverMessage";
+
+/// Body POSTed to a remote DS's `deliverMessage` endpoint on behalf of the
+/// subset of `req.recipients` it is home to.
+#[derive(Debug, Serialize)]
+struct FederatedBlobMessage<'a> {
+    message_id: &'a str,
+    convo_id: &'a str,
+    sender_did: &'a str,
+    /// Base64-encoded, same encoding as [`StoreMessageRequest::encrypted_data`].
+    encrypted_data: &'a str,
+    recipients: Vec<String>,
+    metadata: Option<serde_json::Value>,
+}
+
 /// Request to store an encrypted message blob
 #[derive(Debug, Deserialize)]
 pub struct StoreMessageRequest {
@@ -33,9 +53,10 @@ pub struct StoreMessageRequest {
 pub struct StoreMessageResponse {
     /// Unique message ID
     pub message_id: String,
-    /// R2 blob key
-    pub blob_key: String,
-    /// When the message was stored
+    /// R2 blob key, if this DS persisted a local copy. `None` when every
+    /// recipient resolved to a remote DS - see [`store_message`].
+    pub blob_key: Option<String>,
+    /// When the message was accepted
     pub created_at: chrono::DateTime<chrono::Utc>,
 }
 
@@ -57,86 +78,260 @@ pub struct GetMessageResponse {
 }
 
 /// Store an encrypted message blob
+///
+/// Recipients are resolved to their home DS (`DsResolver`, same component
+/// `blue.catbird.mls.profile` resolution uses elsewhere). Recipients hosted
+/// here are fanned out as before; recipients hosted on another DS are never
+/// inserted into this server's tables - instead the ciphertext and the
+/// subset of recipients that DS is home to are handed to the outbound
+/// queue, grouped per destination so one POST covers every recipient on
+/// that node. Federation-disabled deployments keep the pre-federation
+/// behavior of treating every recipient as local.
 /// POST /api/v1/messages
 pub async fn store_message(
-    claims: Claims,
+    auth_user: AuthUser,
     State(blob_storage): State<Arc<BlobStorage>>,
     State(db_pool): State<DbPool>,
+    State(push_fanout): State<Option<Arc<crate::push::PushFanout>>>,
+    State(federation_config): State<federation::FederationConfig>,
+    State(ds_resolver): State<Arc<dyn DsResolver>>,
+    State(outbound_queue): State<Arc<federation::queue::OutboundQueue>>,
     Json(req): Json<StoreMessageRequest>,
 ) -> Result<Json<StoreMessageResponse>, AppError> {
-    let sender_did = &claims.sub;
-    
+    let sender_did = &auth_user.did;
+
     // Decode base64 encrypted data
     let encrypted_bytes = BASE64.decode(&req.encrypted_data)
         .context("Invalid base64 encrypted data")?;
+    let encrypted_bytes_for_push = encrypted_bytes.clone();
 
     // Generate unique message ID
     let message_id = Uuid::new_v4().to_string();
+    let created_at = chrono::Utc::now();
 
-    // Store blob in R2
-    let blob_key = blob_storage
-        .store_blob(&message_id, encrypted_bytes)
-        .await
-        .context("Failed to store message blob")?;
+    // Resolve every recipient to their home DS, splitting the ones hosted
+    // here from the ones that need to be forwarded, *before* touching local
+    // storage. A resolution failure falls back to local storage rather than
+    // dropping the message - the recipient picks it up if/when they're
+    // provisioned on this DS. Doing this first means a purely-federated send
+    // (no local recipients) never writes an R2 blob or `messages` row that
+    // nothing on this DS would ever reference or clean up.
+    let mut local_recipients: Vec<String> = Vec::with_capacity(req.recipients.len());
+    let mut remote_by_endpoint: HashMap<String, (String, Vec<String>)> = HashMap::new();
 
-    let created_at = chrono::Utc::now();
+    if federation_config.enabled {
+        for recipient_did in &req.recipients {
+            match ds_resolver.resolve(recipient_did).await {
+                Ok(endpoint) if ds_resolver.is_self(&endpoint.did) => {
+                    local_recipients.push(recipient_did.clone());
+                }
+                Ok(endpoint) => {
+                    remote_by_endpoint
+                        .entry(endpoint.endpoint)
+                        .or_insert_with(|| (endpoint.did, Vec::new()))
+                        .1
+                        .push(recipient_did.clone());
+                }
+                Err(e) => {
+                    warn!(
+                        recipient = %recipient_did,
+                        error = %e,
+                        "Failed to resolve recipient's home DS, storing locally"
+                    );
+                    local_recipients.push(recipient_did.clone());
+                }
+            }
+        }
+    } else {
+        local_recipients = req.recipients.clone();
+    }
+
+    // Only persist a blob + messages row locally when at least one recipient
+    // actually lives on this DS.
+    let blob_key = if local_recipients.is_empty() {
+        None
+    } else {
+        let blob_key = blob_storage
+            .store_blob(&message_id, encrypted_bytes)
+            .await
+            .context("Failed to store message blob")?;
+
+        persist_local_message(
+            &db_pool,
+            &message_id,
+            &req,
+            sender_did,
+            &blob_key,
+            created_at,
+            &local_recipients,
+        )
+        .await?;
+
+        Some(blob_key)
+    };
+
+    info!(
+        message_id = %message_id,
+        sender = %sender_did,
+        convo_id = %req.convo_id,
+        local_recipients = local_recipients.len(),
+        remote_destinations = remote_by_endpoint.len(),
+        "Stored encrypted message"
+    );
+
+    // Forward to every remote destination, one POST per DS covering every
+    // recipient it's home to. Enqueued rather than sent inline so a
+    // temporarily unreachable peer retries with backoff instead of losing
+    // the message (see `federation::queue::OutboundQueue`).
+    for (endpoint, (ds_did, recipients)) in remote_by_endpoint {
+        let payload = FederatedBlobMessage {
+            message_id: &message_id,
+            convo_id: &req.convo_id,
+            sender_did: sender_did.as_str(),
+            encrypted_data: &req.encrypted_data,
+            recipients,
+            metadata: req.metadata.clone(),
+        };
+        let payload_bytes = match serde_json::to_vec(&payload) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                error!(message_id = %message_id, target_ds = %ds_did, error = %e, "Failed to serialize federated message payload");
+                continue;
+            }
+        };
+
+        if let Err(e) = outbound_queue
+            .enqueue(
+                &ds_did,
+                &endpoint,
+                DELIVER_MESSAGE_NSID,
+                &payload_bytes,
+                &req.convo_id,
+                "initial enqueue",
+            )
+            .await
+        {
+            error!(message_id = %message_id, target_ds = %ds_did, error = %e, "Failed to enqueue federated message delivery");
+        }
+    }
+
+    // Best-effort push wake-up so recipient devices don't have to poll
+    // list_pending_messages. The raw ciphertext is only ever read out when a
+    // device is registered for PushMode::Raw. Remote recipients are woken up
+    // by their own DS once it accepts the forwarded delivery above.
+    if let Some(push_fanout) = push_fanout.as_ref() {
+        if !local_recipients.is_empty() {
+            push_fanout.notify(
+                db_pool.clone(),
+                req.convo_id.clone(),
+                message_id.clone(),
+                local_recipients,
+                Some(encrypted_bytes_for_push),
+            );
+        }
+    }
+
+    Ok(Json(StoreMessageResponse {
+        message_id,
+        blob_key,
+        created_at,
+    }))
+}
+
+/// Insert the `messages` row and fan it out to every local recipient's
+/// `message_recipients`/`recipient_sync_cursors` rows in a single
+/// transaction, so a crash between the two can never leave a `messages` row
+/// with no recipients attached (or vice versa).
+///
+/// Each recipient row gets a per-recipient monotonic `recipient_seq`,
+/// advanced atomically alongside the insert, so `sync_messages` can hand
+/// clients a gapless, duplicate-free resume point even across reconnects and
+/// restarts.
+async fn persist_local_message(
+    db_pool: &DbPool,
+    message_id: &str,
+    req: &StoreMessageRequest,
+    sender_did: &str,
+    blob_key: &str,
+    created_at: chrono::DateTime<chrono::Utc>,
+    local_recipients: &[String],
+) -> Result<(), AppError> {
+    // Per-conversation retention (disappearing messages / max age), read from
+    // the loose `conversations.metadata` JSONB bag the same way `req.metadata`
+    // is passed through untyped here - there's no generated type for it since
+    // it's a server-side policy, not part of the lexicon record.
+    let retention_seconds: Option<i64> = sqlx::query_scalar!(
+        r#"SELECT (metadata->>'retentionSeconds')::bigint FROM conversations WHERE id = $1"#,
+        req.convo_id,
+    )
+    .fetch_optional(db_pool)
+    .await
+    .context("Failed to read conversation retention policy")?
+    .flatten();
+    let expires_at = retention_seconds.map(|secs| created_at + chrono::Duration::seconds(secs));
+
+    let mut tx = db_pool.begin().await.context("Failed to begin fanout transaction")?;
 
-    // Store metadata in PostgreSQL
     sqlx::query!(
         r#"
-        INSERT INTO messages (id, convo_id, sender_did, blob_key, created_at, metadata)
-        VALUES ($1, $2, $3, $4, $5, $6)
+        INSERT INTO messages (id, convo_id, sender_did, blob_key, created_at, expires_at, metadata)
+        VALUES ($1, $2, $3, $4, $5, $6, $7)
         "#,
         message_id,
         req.convo_id,
         sender_did,
         blob_key,
         created_at,
+        expires_at,
         req.metadata,
     )
-    .execute(&db_pool)
+    .execute(&mut *tx)
     .await
     .context("Failed to store message metadata")?;
 
-    // Store recipient list for fanout
-    for recipient_did in &req.recipients {
+    for recipient_did in local_recipients {
+        let recipient_seq: i64 = sqlx::query_scalar!(
+            r#"
+            INSERT INTO recipient_sync_cursors (recipient_did, next_seq)
+            VALUES ($1, 1)
+            ON CONFLICT (recipient_did) DO UPDATE
+            SET next_seq = recipient_sync_cursors.next_seq + 1
+            RETURNING next_seq
+            "#,
+            recipient_did,
+        )
+        .fetch_one(&mut *tx)
+        .await
+        .context("Failed to advance recipient sync cursor")?;
+
         sqlx::query!(
             r#"
-            INSERT INTO message_recipients (message_id, recipient_did, delivered)
-            VALUES ($1, $2, false)
+            INSERT INTO message_recipients (message_id, recipient_did, delivered, recipient_seq)
+            VALUES ($1, $2, false, $3)
             "#,
             message_id,
             recipient_did,
+            recipient_seq,
         )
-        .execute(&db_pool)
+        .execute(&mut *tx)
         .await
         .context("Failed to store recipient")?;
     }
 
-    info!(
-        message_id = %message_id,
-        sender = %sender_did,
-        convo_id = %req.convo_id,
-        recipients = req.recipients.len(),
-        "Stored encrypted message"
-    );
+    tx.commit().await.context("Failed to commit fanout transaction")?;
 
-    Ok(Json(StoreMessageResponse {
-        message_id,
-        blob_key,
-        created_at,
-    }))
+    Ok(())
 }
 
 /// Retrieve an encrypted message blob
 /// GET /api/v1/messages/:message_id
 pub async fn get_message(
-    claims: Claims,
+    auth_user: AuthUser,
     State(blob_storage): State<Arc<BlobStorage>>,
     State(db_pool): State<DbPool>,
     Path(message_id): Path<String>,
 ) -> Result<Json<GetMessageResponse>, AppError> {
-    let requester_did = &claims.sub;
+    let requester_did = &auth_user.did;
 
     // Fetch message metadata from PostgreSQL
     let message = sqlx::query!(
@@ -192,53 +387,251 @@ pub async fn get_message(
     }))
 }
 
-/// List pending messages for the current user
-/// GET /api/v1/messages/pending
-pub async fn list_pending_messages(
-    claims: Claims,
+/// Default and maximum page size for [`sync_messages`].
+const SYNC_DEFAULT_LIMIT: i64 = 100;
+const SYNC_MAX_LIMIT: i64 = 500;
+
+/// Query parameters for [`sync_messages`].
+#[derive(Debug, Deserialize)]
+pub struct SyncMessagesQuery {
+    /// Highest `recipient_seq` the client has already seen. Defaults to 0
+    /// (the start of the mailbox) when omitted.
+    #[serde(default)]
+    pub since: i64,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SyncMessagesResponse {
+    pub messages: Vec<PendingMessage>,
+    /// `recipient_seq` of the last message in this page, or `since` unchanged
+    /// if the page is empty. Persist this and pass it back as `since` to
+    /// resume with no gaps or duplicates.
+    pub next_cursor: i64,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct PendingMessage {
+    pub message_id: String,
+    pub convo_id: String,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub recipient_seq: i64,
+}
+
+/// Incremental mailbox sync: returns messages with `recipient_seq > since`,
+/// in ascending order, plus a `next_cursor` to resume from. Unlike a
+/// `LIMIT`-and-`ORDER BY created_at DESC` poll, a client that persists
+/// `next_cursor` can never silently drop messages by falling behind -
+/// marking a message delivered via `get_message` does not advance or reuse
+/// the cursor, so nothing is skipped even if delivery is retried.
+/// GET /api/v1/messages/sync?since=<seq>&limit=<n>
+pub async fn sync_messages(
+    auth_user: AuthUser,
     State(db_pool): State<DbPool>,
-) -> Result<Json<Vec<PendingMessage>>, AppError> {
-    let recipient_did = &claims.sub;
+    Query(params): Query<SyncMessagesQuery>,
+) -> Result<Json<SyncMessagesResponse>, AppError> {
+    let recipient_did = &auth_user.did;
+    let limit = params.limit.unwrap_or(SYNC_DEFAULT_LIMIT).clamp(1, SYNC_MAX_LIMIT);
 
     let messages = sqlx::query_as!(
         PendingMessage,
         r#"
-        SELECT m.id as message_id, m.convo_id, m.created_at
-        FROM messages m
-        INNER JOIN message_recipients mr ON m.id = mr.message_id
-        WHERE mr.recipient_did = $1 AND mr.delivered = false
-        ORDER BY m.created_at DESC
-        LIMIT 100
+        SELECT m.id as message_id, m.convo_id, m.created_at, mr.recipient_seq
+        FROM message_recipients mr
+        INNER JOIN messages m ON m.id = mr.message_id
+        WHERE mr.recipient_did = $1 AND mr.recipient_seq > $2
+        ORDER BY mr.recipient_seq ASC
+        LIMIT $3
         "#,
         recipient_did,
+        params.since,
+        limit,
     )
     .fetch_all(&db_pool)
     .await
-    .context("Failed to fetch pending messages")?;
+    .context("Failed to fetch message sync page")?;
+
+    let next_cursor = messages
+        .last()
+        .map(|m| m.recipient_seq)
+        .unwrap_or(params.since);
 
-    Ok(Json(messages))
+    Ok(Json(SyncMessagesResponse {
+        messages,
+        next_cursor,
+    }))
 }
 
-#[derive(Debug, Serialize)]
-pub struct PendingMessage {
-    pub message_id: String,
-    pub convo_id: String,
-    pub created_at: chrono::DateTime<chrono::Utc>,
+/// List pending (undelivered) messages for the current user.
+/// Thin wrapper over [`sync_messages`] with `since = 0`, kept for existing
+/// clients that haven't migrated to cursor-based sync yet.
+/// GET /api/v1/messages/pending
+pub async fn list_pending_messages(
+    auth_user: AuthUser,
+    State(db_pool): State<DbPool>,
+) -> Result<Json<Vec<PendingMessage>>, AppError> {
+    let page = sync_messages(
+        auth_user,
+        State(db_pool),
+        Query(SyncMessagesQuery { since: 0, limit: None }),
+    )
+    .await?;
+
+    Ok(Json(page.0.messages))
+}
+
+/// Request to register or update a device's push token
+#[derive(Debug, Deserialize)]
+pub struct RegisterPushTokenRequest {
+    pub provider: crate::push::PushProviderKind,
+    pub token: String,
+    #[serde(default)]
+    pub mode: crate::push::PushMode,
+}
+
+/// Register (or replace) the push token for a device, so future message
+/// fan-out can wake it up instead of relying on `list_pending_messages` polling.
+/// POST /api/v1/devices/:device_id/push-token
+pub async fn register_push_token(
+    auth_user: AuthUser,
+    State(db_pool): State<DbPool>,
+    Path(device_id): Path<String>,
+    Json(req): Json<RegisterPushTokenRequest>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let user_did = &auth_user.did;
+
+    crate::push::register_push_token(
+        &db_pool,
+        &device_id,
+        user_did,
+        req.provider,
+        &req.token,
+        req.mode,
+    )
+    .await
+    .context("Failed to register push token")?;
+
+    info!(
+        device_id = %device_id,
+        user_did = %user_did,
+        provider = ?req.provider,
+        mode = ?req.mode,
+        "Registered push token"
+    );
+
+    Ok(Json(serde_json::json!({ "ok": true })))
+}
+
+/// Admin path to force-delete a message: removes the R2 blob and every
+/// `message_recipients` row for it, regardless of delivery or expiry state.
+/// For the routine case (expired or fully-delivered) prefer letting
+/// `jobs::blob_retention` reclaim it - this exists for moderation/takedown
+/// and for operators clearing storage by hand.
+/// DELETE /api/v1/messages/:message_id
+pub async fn delete_message(
+    auth_user: AuthUser,
+    State(blob_storage): State<Arc<BlobStorage>>,
+    State(db_pool): State<DbPool>,
+    Path(message_id): Path<String>,
+) -> Result<Json<serde_json::Value>, AppError> {
+    let requester_did = &auth_user.did;
+
+    // This is an admin-only, cross-recipient operation (unlike `get_message`,
+    // which scopes to `mr.recipient_did = requester`), so membership alone
+    // isn't enough - require the requester be an admin of the message's own
+    // conversation before anything is deleted.
+    let convo_id = sqlx::query_scalar!("SELECT convo_id FROM messages WHERE id = $1", message_id)
+        .fetch_optional(&db_pool)
+        .await
+        .context("Failed to look up message")?
+        .ok_or_else(|| AppError::status(StatusCode::NOT_FOUND, "Message not found"))?;
+
+    let is_admin = sqlx::query_scalar!(
+        r#"
+        SELECT is_admin
+        FROM members
+        WHERE convo_id = $1 AND member_did = $2 AND left_at IS NULL
+        "#,
+        convo_id,
+        requester_did,
+    )
+    .fetch_optional(&db_pool)
+    .await
+    .context("Failed to verify admin status")?
+    .unwrap_or(false);
+
+    if !is_admin {
+        return Err(AppError::status(
+            StatusCode::FORBIDDEN,
+            "Not authorized to delete this message",
+        ));
+    }
+
+    // Blob first, then DB rows: if we crash between the two, the message row
+    // survives and the next admin delete (or the retention sweep, once
+    // expires_at/delivery catches up) retries against an already-missing R2
+    // key, which is a no-op. Deleting the DB row first would instead leave
+    // an orphaned blob with nothing pointing at it.
+    blob_storage
+        .delete_blob(&message_id)
+        .await
+        .context("Failed to delete message blob")?;
+
+    let mut tx = db_pool.begin().await.context("Failed to begin delete transaction")?;
+    sqlx::query!(
+        "DELETE FROM message_recipients WHERE message_id = $1",
+        message_id,
+    )
+    .execute(&mut *tx)
+    .await
+    .context("Failed to delete message recipients")?;
+
+    let deleted = sqlx::query!("DELETE FROM messages WHERE id = $1", message_id,)
+        .execute(&mut *tx)
+        .await
+        .context("Failed to delete message")?;
+    tx.commit().await.context("Failed to commit delete transaction")?;
+
+    info!(
+        message_id = %message_id,
+        requested_by = %requester_did,
+        rows_deleted = deleted.rows_affected(),
+        "Force-deleted message"
+    );
+
+    Ok(Json(serde_json::json!({ "ok": true })))
 }
 
 /// Error type for message handlers
-pub struct AppError(anyhow::Error);
+pub enum AppError {
+    /// Unexpected failure (DB error, R2 error, ...) - logged and returned as 500.
+    Internal(anyhow::Error),
+    /// Expected rejection (not found, not authorized, ...) with the status
+    /// code it should actually carry, rather than flattening to 500.
+    Status(StatusCode, String),
+}
+
+impl AppError {
+    fn status(code: StatusCode, message: impl Into<String>) -> Self {
+        Self::Status(code, message.into())
+    }
+}
 
 impl IntoResponse for AppError {
     fn into_response(self) -> Response {
-        error!("Handler error: {:?}", self.0);
-        (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            Json(serde_json::json!({
-                "error": format!("{}", self.0)
-            })),
-        )
-            .into_response()
+        match self {
+            Self::Internal(err) => {
+                error!("Handler error: {:?}", err);
+                (
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    Json(serde_json::json!({ "error": format!("{}", err) })),
+                )
+                    .into_response()
+            }
+            Self::Status(code, message) => {
+                (code, Json(serde_json::json!({ "error": message }))).into_response()
+            }
+        }
     }
 }
 
@@ -247,6 +640,6 @@ where
     E: Into<anyhow::Error>,
 {
     fn from(err: E) -> Self {
-        Self(err.into())
+        Self::Internal(err.into())
     }
 }
@@ -1,5 +1,6 @@
 use axum::{extract::State, http::StatusCode, Json};
 use jacquard_axum::ExtractXrpc;
+use sqlx::FromRow;
 use tracing::{error, info};
 
 use crate::{
@@ -7,12 +8,25 @@ use crate::{
     generated::blue_catbird::mlsChat::get_reports::{
         GetReportsOutput, GetReportsRequest, ReportView,
     },
+    query::SelectBuilder,
     sqlx_jacquard::chrono_to_datetime,
     storage::DbPool,
 };
 
 const NSID: &str = "blue.catbird.mlsChat.getReports";
 
+#[derive(Debug, FromRow)]
+struct ReportRow {
+    id: String,
+    reporter_did: String,
+    reported_did: String,
+    category: String,
+    created_at: chrono::DateTime<chrono::Utc>,
+    status: String,
+    resolved_by_did: Option<String>,
+    resolved_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
 /// Query reports for a conversation.
 /// GET /xrpc/blue.catbird.mlsChat.getReports
 #[tracing::instrument(skip(pool, auth_user))]
@@ -32,55 +46,24 @@ pub async fn get_reports(
 
     let limit = input.limit.unwrap_or(50).min(100);
 
-    let rows: Vec<(
-        String,
-        String,
-        String,
-        String,
-        chrono::DateTime<chrono::Utc>,
-        String,
-        Option<String>,
-        Option<chrono::DateTime<chrono::Utc>>,
-    )> = if let Some(ref status) = input.status {
-        sqlx::query_as(
-            "SELECT id, reporter_did, reported_did, category, created_at, status,
-                    resolved_by_did, resolved_at
-             FROM reports
-             WHERE convo_id = $1 AND status = $2
-             ORDER BY created_at DESC
-             LIMIT $3",
-        )
-        .bind(convo_id)
-        .bind(status.as_ref())
-        .bind(limit)
-        .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            error!("Database query failed: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-    } else {
-        sqlx::query_as(
-            "SELECT id, reporter_did, reported_did, category, created_at, status,
-                    resolved_by_did, resolved_at
-             FROM reports
-             WHERE convo_id = $1
-             ORDER BY created_at DESC
-             LIMIT $2",
-        )
-        .bind(convo_id)
-        .bind(limit)
-        .fetch_all(&pool)
-        .await
-        .map_err(|e| {
-            error!("Database query failed: {}", e);
-            StatusCode::INTERNAL_SERVER_ERROR
-        })?
-    };
+    let rows: Vec<ReportRow> = SelectBuilder::new(
+        "id, reporter_did, reported_did, category, created_at, status, resolved_by_did, resolved_at",
+        "reports",
+    )
+    .filter("convo_id", convo_id.to_string())
+    .filter_opt("status", input.status.as_ref().map(|s| s.as_ref().to_string()))
+    .order_by("created_at DESC")
+    .limit(limit as i64)
+    .fetch_all(&pool)
+    .await
+    .map_err(|e| {
+        error!("Database query failed: {}", e);
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
 
     let mut reports: Vec<ReportView<'static>> = Vec::with_capacity(rows.len());
 
-    for (
+    for ReportRow {
         id,
         reporter_did,
         reported_did,
@@ -89,7 +72,7 @@ pub async fn get_reports(
         status,
         resolved_by_did,
         resolved_at,
-    ) in rows
+    } in rows
     {
         let reporter_did = reporter_did.parse().map_err(|e| {
             error!("Failed to parse reporter DID '{}': {}", reporter_did, e);
@@ -129,6 +112,10 @@ pub async fn get_reports(
         reports.len(),
         crate::crypto::redact_for_log(convo_id)
     );
+    crate::metrics::record_reports_queried(
+        reports.len(),
+        input.status.as_ref().map(AsRef::as_ref).unwrap_or("all"),
+    );
 
     Ok(Json(GetReportsOutput {
         reports,
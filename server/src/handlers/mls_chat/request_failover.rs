@@ -1,11 +1,14 @@
 use axum::{extract::State, http::StatusCode, Json};
 use serde::{Deserialize, Serialize};
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tracing::{error, info, warn};
 
 use crate::{
     auth::AuthUser,
-    federation::{self, DsResolver, FederatedBackend, FederationConfig, SequencerTransfer},
+    federation::{
+        self, protocol, DsResolver, FederatedBackend, FederationConfig, SequencerTransfer,
+        ServiceAuthClient,
+    },
     storage::DbPool,
 };
 
@@ -23,6 +26,11 @@ pub struct RequestFailoverOutput {
     pub new_sequencer_did: String,
     pub convo_id: String,
     pub epoch: i32,
+    /// Federation protocol version negotiated with the conversation's other
+    /// participant DSes before the `transferSequencer` broadcast was sent.
+    /// Equal to [`protocol::PROTOCOL_VERSION`] when no negotiation was
+    /// needed (e.g. this DS was already the sequencer).
+    pub negotiated_protocol_version: u32,
 }
 
 /// POST /xrpc/blue.catbird.mlsChat.requestFailover
@@ -38,16 +46,18 @@ pub struct RequestFailoverOutput {
     fed_config,
     federated_backend,
     outbound_queue,
+    service_auth,
     auth_user,
     input
 ))]
 pub async fn request_failover(
     State(pool): State<DbPool>,
-    State(resolver): State<Arc<DsResolver>>,
+    State(resolver): State<Arc<dyn DsResolver>>,
     State(sequencer_transfer): State<Arc<SequencerTransfer>>,
     State(fed_config): State<FederationConfig>,
     State(federated_backend): State<Arc<FederatedBackend>>,
     State(outbound_queue): State<Arc<federation::queue::OutboundQueue>>,
+    State(service_auth): State<Arc<ServiceAuthClient>>,
     auth_user: AuthUser,
     Json(input): Json<RequestFailoverInput>,
 ) -> Result<Json<RequestFailoverOutput>, StatusCode> {
@@ -88,6 +98,7 @@ pub async fn request_failover(
             new_sequencer_did: self_did.clone(),
             convo_id: input.convo_id,
             epoch,
+            negotiated_protocol_version: protocol::PROTOCOL_VERSION,
         }));
     }
 
@@ -101,27 +112,19 @@ pub async fn request_failover(
                 sequencer = %crate::crypto::redact_for_log(&current_seq),
                 "Cannot resolve sequencer endpoint, assuming unreachable"
             );
-            do_assume(
+            let output = attempt_takeover(
+                &resolver,
+                &service_auth,
                 &sequencer_transfer,
+                &federated_backend,
+                &outbound_queue,
                 &input.convo_id,
                 self_did,
                 epoch,
                 &current_seq,
             )
             .await?;
-            let new_epoch = increment_epoch(&pool, &input.convo_id).await?;
-            broadcast_sequencer_change(
-                &federated_backend,
-                &outbound_queue,
-                &input.convo_id,
-                self_did,
-                new_epoch,
-            );
-            return Ok(Json(RequestFailoverOutput {
-                new_sequencer_did: self_did.clone(),
-                convo_id: input.convo_id,
-                epoch: new_epoch,
-            }));
+            return Ok(Json(output));
         }
     };
 
@@ -164,81 +167,331 @@ pub async fn request_failover(
         }
     }
 
-    // Sequencer is unreachable — assume the role
-    do_assume(
+    // Sequencer is unreachable to us — confirm with the rest of the
+    // conversation's participant DSes before assuming the role.
+    let output = attempt_takeover(
+        &resolver,
+        &service_auth,
         &sequencer_transfer,
+        &federated_backend,
+        &outbound_queue,
         &input.convo_id,
         self_did,
         epoch,
         &current_seq,
     )
     .await?;
-    let new_epoch = increment_epoch(&pool, &input.convo_id).await?;
 
-    // Best-effort broadcast to all remote DSes (non-blocking)
-    broadcast_sequencer_change(
-        &federated_backend,
-        &outbound_queue,
-        &input.convo_id,
-        self_did,
-        new_epoch,
-    );
+    Ok(Json(output))
+}
 
-    Ok(Json(RequestFailoverOutput {
-        new_sequencer_did: self_did.clone(),
-        convo_id: input.convo_id,
-        epoch: new_epoch,
-    }))
+/// Outcome of polling the conversation's other participant DSes before a
+/// candidate assumes the sequencer role.
+enum QuorumDecision {
+    /// Fewer than two participant DSes remain once the current (unreachable)
+    /// sequencer is excluded — quorum polling is meaningless, so fall back
+    /// to the original single-node behavior.
+    SingleNode,
+    /// We are the deterministic candidate and a strict majority of votes,
+    /// including our own, say the sequencer is unreachable.
+    Proceed,
+    /// A strict majority can still reach the sequencer — don't take over.
+    Denied,
+    /// Another participant DS has a lower canonical DID and should be the
+    /// one to attempt the takeover instead of us.
+    Defer { candidate: String },
 }
 
-/// Atomically increment the conversation epoch after a failover to prevent
-/// the old and new sequencer from accepting commits at the same epoch.
-async fn increment_epoch(pool: &DbPool, convo_id: &str) -> Result<i32, StatusCode> {
-    let new_epoch: i32 = sqlx::query_scalar(
-        "UPDATE conversations SET current_epoch = current_epoch + 1 WHERE id = $1 RETURNING current_epoch",
-    )
-    .bind(convo_id)
-    .fetch_one(pool)
-    .await
-    .map_err(|e| {
-        error!(
-            convo_id = %crate::crypto::redact_for_log(convo_id),
-            error = %e,
-            "Failed to increment epoch"
+/// Decide whether this DS may take over as sequencer for `convo_id`.
+///
+/// `participant_ds_dids` comes from [`FederatedBackend::get_participant_ds_dids`].
+/// The current sequencer and this DS are excluded from the set of DSes
+/// polled — we already know the sequencer is down, and our own vote
+/// (`self_did` believes it's unreachable, or we wouldn't be here) is
+/// implicit.
+async fn decide_quorum(
+    resolver: &dyn DsResolver,
+    service_auth: &ServiceAuthClient,
+    convo_id: &str,
+    current_seq: &str,
+    self_did: &str,
+    participant_ds_dids: Vec<String>,
+) -> (QuorumDecision, Vec<protocol::PeerProtocolInfo>) {
+    let self_canonical = crate::identity::canonical_did(self_did);
+    let current_seq_canonical = crate::identity::canonical_did(current_seq);
+
+    let mut others: Vec<String> = participant_ds_dids
+        .into_iter()
+        .filter(|d| crate::identity::canonical_did(d) != current_seq_canonical)
+        .collect();
+    others.sort_by(|a, b| crate::identity::canonical_did(a).cmp(crate::identity::canonical_did(b)));
+    others.dedup_by(|a, b| crate::identity::canonical_did(a) == crate::identity::canonical_did(b));
+
+    // Deterministic candidate: the lowest canonical DID among the
+    // remaining (non-sequencer) participant DSes that is actually
+    // reachable - a dead lowest-DID DS would otherwise make every other
+    // participant defer to a candidate that can never run the takeover,
+    // wedging the conversation on the dead sequencer forever. We know we
+    // ourselves are reachable (we're the one running this), so we don't
+    // probe ourselves; any other candidate is probed via `healthCheck`
+    // before we accept it ahead of us.
+    for candidate in &others {
+        let candidate_canonical = crate::identity::canonical_did(candidate);
+        if candidate_canonical == self_canonical {
+            break;
+        }
+        if !probe_candidate_reachable(resolver, candidate).await {
+            continue;
+        }
+        return (
+            QuorumDecision::Defer {
+                candidate: candidate.clone(),
+            },
+            Vec::new(),
         );
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?;
+    }
 
-    info!(
-        convo_id = %crate::crypto::redact_for_log(convo_id),
-        new_epoch,
-        "Epoch incremented after failover"
-    );
-    Ok(new_epoch)
+    let voters: Vec<String> = others
+        .into_iter()
+        .filter(|d| crate::identity::canonical_did(d) != self_canonical)
+        .collect();
+
+    if voters.is_empty() {
+        return (QuorumDecision::SingleNode, Vec::new());
+    }
+
+    let total_votes = voters.len() + 1; // + our own vote
+    let mut unreachable_votes = 1; // we already observed the sequencer as unreachable
+    let mut peer_infos = Vec::with_capacity(voters.len());
+
+    for voter in &voters {
+        let voter_says_unreachable =
+            match cast_reachability_vote(resolver, service_auth, convo_id, current_seq, voter).await
+            {
+                Ok((reachable, peer)) => {
+                    peer_infos.push(peer);
+                    !reachable
+                }
+                // Can't resolve or reach the voter within its timeout —
+                // treat as an "unreachable" vote per the failover contract.
+                // We also have no protocol info for it, so it's simply
+                // left out of the negotiation below.
+                Err(_) => true,
+            };
+        if voter_says_unreachable {
+            unreachable_votes += 1;
+        }
+    }
+
+    let decision = if unreachable_votes * 2 > total_votes {
+        QuorumDecision::Proceed
+    } else {
+        QuorumDecision::Denied
+    };
+    (decision, peer_infos)
 }
 
-async fn do_assume(
-    transfer: &SequencerTransfer,
+/// Ask one other participant DS whether *it* can reach the current
+/// sequencer's `healthCheck`, with a per-vote timeout. The same response
+/// also carries the voter's own protocol version/capabilities (see
+/// [`crate::handlers::ds::check_reachability`]), so this doubles as the
+/// data source for [`negotiate_protocol`] without a second round trip.
+async fn cast_reachability_vote(
+    resolver: &dyn DsResolver,
+    service_auth: &ServiceAuthClient,
+    convo_id: &str,
+    current_seq: &str,
+    voter_ds: &str,
+) -> Result<(bool, protocol::PeerProtocolInfo), ()> {
+    const VOTE_TIMEOUT: Duration = Duration::from_secs(5);
+    const METHOD: &str = "blue.catbird.mls.ds.checkReachability";
+
+    let endpoint = resolver.resolve(voter_ds).await.map_err(|_| ())?.endpoint;
+    let token = service_auth.sign_request(voter_ds, METHOD).map_err(|_| ())?;
+
+    let client = reqwest::Client::builder()
+        .timeout(VOTE_TIMEOUT)
+        .build()
+        .map_err(|_| ())?;
+
+    let resp = client
+        .get(format!("{}/xrpc/{METHOD}", endpoint.trim_end_matches('/')))
+        .bearer_auth(token)
+        .query(&[("convo_id", convo_id), ("sequencer_did", current_seq)])
+        .send()
+        .await
+        .map_err(|_| ())?;
+
+    if !resp.status().is_success() {
+        return Err(());
+    }
+
+    let body: serde_json::Value = resp.json().await.map_err(|_| ())?;
+    let reachable = body
+        .get("reachable")
+        .and_then(|v| v.as_bool())
+        .unwrap_or(false);
+    Ok((reachable, protocol::PeerProtocolInfo::from_health_check_body(&body)))
+}
+
+/// Probe whether `candidate_ds` itself is up, by resolving its endpoint and
+/// hitting its own `healthCheck` (unauthenticated, unlike
+/// [`cast_reachability_vote`] which asks a third party about the
+/// *sequencer*). Used to skip a dead lowest-canonical-DID candidate in
+/// [`decide_quorum`] instead of every other participant deferring to a DS
+/// that can never run the takeover.
+async fn probe_candidate_reachable(resolver: &dyn DsResolver, candidate_ds: &str) -> bool {
+    const PROBE_TIMEOUT: Duration = Duration::from_secs(5);
+    const METHOD: &str = "blue.catbird.mls.ds.healthCheck";
+
+    let Ok(endpoint) = resolver.resolve(candidate_ds).await else {
+        return false;
+    };
+    let Ok(client) = reqwest::Client::builder().timeout(PROBE_TIMEOUT).build() else {
+        return false;
+    };
+
+    client
+        .get(format!("{}/xrpc/{METHOD}", endpoint.endpoint.trim_end_matches('/')))
+        .send()
+        .await
+        .is_ok_and(|resp| resp.status().is_success())
+}
+
+/// Gate a sequencer takeover behind quorum, then perform it.
+///
+/// Exists so both unreachability paths in [`request_failover`] (DID
+/// resolution failure and a failed HTTP health check) share the same
+/// quorum-then-assume sequence instead of duplicating it.
+#[allow(clippy::too_many_arguments)]
+async fn attempt_takeover(
+    resolver: &dyn DsResolver,
+    service_auth: &ServiceAuthClient,
+    sequencer_transfer: &SequencerTransfer,
+    federated_backend: &Arc<FederatedBackend>,
+    outbound_queue: &Arc<federation::queue::OutboundQueue>,
     convo_id: &str,
     self_did: &str,
     epoch: i32,
-    expected_sequencer: &str,
-) -> Result<(), StatusCode> {
-    transfer
-        .assume_sequencer_role(convo_id, expected_sequencer)
+    current_seq: &str,
+) -> Result<RequestFailoverOutput, StatusCode> {
+    let participant_ds_dids = federated_backend
+        .get_participant_ds_dids(convo_id)
         .await
         .map_err(|e| {
-            error!("Failed to assume sequencer role: {}", e);
+            error!(
+                convo_id = %crate::crypto::redact_for_log(convo_id),
+                error = %e,
+                "Failed to list participant DS DIDs for failover quorum"
+            );
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let (decision, peer_infos) = decide_quorum(
+        resolver,
+        service_auth,
+        convo_id,
+        current_seq,
+        self_did,
+        participant_ds_dids,
+    )
+    .await;
+
+    match decision {
+        QuorumDecision::SingleNode | QuorumDecision::Proceed => {}
+        QuorumDecision::Denied => {
+            info!(
+                convo_id = %crate::crypto::redact_for_log(convo_id),
+                "Failover quorum denied: a majority of participant DSes can still reach the sequencer"
+            );
+            return Err(StatusCode::CONFLICT);
+        }
+        QuorumDecision::Defer { candidate } => {
+            info!(
+                convo_id = %crate::crypto::redact_for_log(convo_id),
+                candidate = %crate::crypto::redact_for_log(&candidate),
+                "Deferring failover to the lower-canonical-DID candidate DS"
+            );
+            return Err(StatusCode::CONFLICT);
+        }
+    }
+
+    let negotiated = negotiate_protocol(convo_id, &peer_infos)?;
+
+    let (_, new_epoch) = sequencer_transfer
+        .assume_sequencer_role_and_advance_epoch(convo_id, current_seq)
+        .await
+        .map_err(|e| {
+            error!(
+                convo_id = %crate::crypto::redact_for_log(convo_id),
+                error = %e,
+                "Failed to assume sequencer role"
+            );
             StatusCode::INTERNAL_SERVER_ERROR
         })?;
 
     info!(
         convo_id = %crate::crypto::redact_for_log(convo_id),
         new_sequencer = %crate::crypto::redact_for_log(self_did),
-        epoch,
-        "Failover complete — assumed sequencer role"
+        new_epoch,
+        negotiated_protocol_version = negotiated.version,
+        "Failover complete — assumed sequencer role and advanced epoch"
     );
-    Ok(())
+
+    broadcast_sequencer_change(
+        federated_backend,
+        outbound_queue,
+        convo_id,
+        self_did,
+        new_epoch,
+        negotiated.version,
+    );
+
+    Ok(RequestFailoverOutput {
+        new_sequencer_did: self_did.to_string(),
+        convo_id: convo_id.to_string(),
+        epoch: new_epoch,
+        negotiated_protocol_version: negotiated.version,
+    })
+}
+
+/// Negotiate a federation protocol version and capability set across every
+/// peer [`decide_quorum`] heard back from, ahead of the `transferSequencer`
+/// broadcast sent to those same participant DSes.
+///
+/// A voter that never responded to the reachability vote contributes no
+/// protocol info here either — we can't negotiate with a peer we can't
+/// reach, and the broadcast to it will fail through the normal
+/// outbound-queue retry path regardless. A voter that *did* respond but
+/// reports a version below [`protocol::MIN_SUPPORTED_PROTOCOL_VERSION`]
+/// refuses the whole takeover: proceeding would enqueue a
+/// `transferSequencer` that peer can't be trusted to understand.
+fn negotiate_protocol(
+    convo_id: &str,
+    peer_infos: &[protocol::PeerProtocolInfo],
+) -> Result<protocol::NegotiatedProtocol, StatusCode> {
+    let mut negotiated = protocol::NegotiatedProtocol {
+        version: protocol::PROTOCOL_VERSION,
+        shared_capabilities: protocol::CAPABILITIES.iter().map(|c| c.to_string()).collect(),
+    };
+
+    for peer in peer_infos {
+        let peer_negotiated = protocol::negotiate(peer).map_err(|e| {
+            warn!(
+                convo_id = %crate::crypto::redact_for_log(convo_id),
+                error = %e,
+                "Refusing takeover: a participant DS speaks an incompatible federation protocol version"
+            );
+            StatusCode::UPGRADE_REQUIRED
+        })?;
+
+        negotiated.version = negotiated.version.min(peer_negotiated.version);
+        negotiated
+            .shared_capabilities
+            .retain(|c| peer_negotiated.shared_capabilities.iter().any(|p| p == c));
+    }
+
+    Ok(negotiated)
 }
 
 /// Spawn a background task to broadcast the sequencer change to all remote DSes.
@@ -249,6 +502,7 @@ fn broadcast_sequencer_change(
     convo_id: &str,
     new_sequencer_did: &str,
     epoch: i32,
+    negotiated_protocol_version: u32,
 ) {
     let fb = Arc::clone(federated_backend);
     let oq = Arc::clone(outbound_queue);
@@ -271,6 +525,7 @@ fn broadcast_sequencer_change(
         let payload = serde_json::json!({
             "convoId": convo_id,
             "currentEpoch": epoch,
+            "protocolVersion": negotiated_protocol_version,
         });
         let payload_bytes = match serde_json::to_vec(&payload) {
             Ok(b) => b,
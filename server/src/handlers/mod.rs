@@ -1,5 +1,9 @@
 // Handler modules for API endpoints
+pub mod ds;
+
 mod add_members;
+mod add_reaction;
+mod batch;
 mod create_convo;
 mod get_commits;
 mod get_convos;
@@ -8,12 +12,18 @@ mod get_key_packages;
 mod get_messages;
 mod get_welcome;
 mod leave_convo;
+mod messages;
 mod publish_key_package;
+mod remove_reaction;
 mod send_message;
+mod send_typing_indicator;
+mod subscribe_convo;
 mod update_cursor;
 
 // Re-export handlers
 pub use add_members::add_members;
+pub use add_reaction::add_reaction;
+pub use batch::batch;
 pub use create_convo::create_convo;
 pub use get_commits::get_commits;
 pub use get_convos::get_convos;
@@ -22,6 +32,13 @@ pub use get_key_packages::get_key_packages;
 pub use get_messages::get_messages;
 pub use get_welcome::get_welcome;
 pub use leave_convo::leave_convo;
+pub use messages::{
+    delete_message, get_message, list_pending_messages, register_push_token, store_message,
+    sync_messages,
+};
 pub use publish_key_package::publish_key_package;
+pub use remove_reaction::remove_reaction;
 pub use send_message::send_message;
+pub use send_typing_indicator::send_typing_indicator;
+pub use subscribe_convo::subscribe_convo;
 pub use update_cursor::update_cursor;
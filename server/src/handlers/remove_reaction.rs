@@ -3,8 +3,10 @@ use std::sync::Arc;
 use tracing::{error, info};
 
 use crate::{
+    actors::{ActorRegistry, ConvoEvent, ConvoMessage},
     auth::AuthUser,
     db,
+    federation::{RemoteEphemeralEvent, RemoteNodeRegistry},
     generated::blue::catbird::mls::remove_reaction::{Input, Output, OutputData, NSID},
     realtime::{SseState, StreamEvent},
     storage::DbPool,
@@ -12,10 +14,12 @@ use crate::{
 
 /// Remove a reaction from a message
 /// POST /xrpc/blue.catbird.mls.removeReaction
-#[tracing::instrument(skip(pool, sse_state, auth_user))]
+#[tracing::instrument(skip(pool, sse_state, actor_registry, remote_node_registry, auth_user))]
 pub async fn remove_reaction(
     State(pool): State<DbPool>,
     State(sse_state): State<Arc<SseState>>,
+    State(actor_registry): State<Arc<ActorRegistry>>,
+    State(remote_node_registry): State<Option<Arc<RemoteNodeRegistry>>>,
     auth_user: AuthUser,
     Json(input): Json<Input>,
 ) -> Result<Json<Output>, StatusCode> {
@@ -87,6 +91,38 @@ pub async fn remove_reaction(
         // Don't fail the request, reaction was still removed
     }
 
+    // Also publish to subscribeConvo long-poll waiters instead of dropping
+    // the event on the floor for clients not using SSE.
+    match actor_registry.get_or_spawn(&input.convo_id).await {
+        Ok(actor_ref) => {
+            let _ = actor_ref.cast(ConvoMessage::Notify(ConvoEvent::Reaction {
+                message_id: input.message_id.clone(),
+                did: user_did.clone(),
+                reaction: input.reaction.clone(),
+                action: "remove".to_string(),
+            }));
+        }
+        Err(e) => error!("Failed to get conversation actor for notify: {}", e),
+    }
+
+    // Forward to members hosted on a different delivery service. Best-effort:
+    // failures here never affect the local success response.
+    if let Some(registry) = remote_node_registry {
+        if let Ok(members) = db::list_members(&pool, &input.convo_id).await {
+            registry.broadcast(
+                input.convo_id.clone(),
+                members.into_iter().map(|m| m.member_did).collect(),
+                RemoteEphemeralEvent::Reaction {
+                    message_id: input.message_id.clone(),
+                    did: user_did.clone(),
+                    reaction: input.reaction.clone(),
+                    action: "remove".to_string(),
+                },
+            );
+        }
+    }
+
+    crate::metrics::record_reaction("remove");
     info!("Reaction removed successfully");
 
     Ok(Json(Output::from(OutputData { success: true })))
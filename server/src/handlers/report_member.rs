@@ -94,6 +94,7 @@ pub async fn report_member(
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
 
+    crate::metrics::record_report_submitted();
     info!("✅ [report_member] SUCCESS - report {} created", report_id);
 
     Ok(Json(Output::from(OutputData {
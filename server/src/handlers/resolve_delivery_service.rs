@@ -3,7 +3,7 @@ use tracing::debug;
 
 use crate::{
     auth::AuthUser,
-    federation::{DsResolver, FederationError},
+    federation::{DsResolver, FederationError, RemoteDsResolver},
     generated::blue_catbird::mls::resolve_delivery_service::ResolveDeliveryServiceOutput,
     storage::DbPool,
 };
@@ -11,9 +11,9 @@ use crate::{
 /// GET /xrpc/blue.catbird.mls.resolveDeliveryService
 ///
 /// Client-facing endpoint to resolve a user's delivery service endpoint.
-#[tracing::instrument(skip(pool, _auth_user, query))]
+#[tracing::instrument(skip(_pool, _auth_user, query))]
 pub async fn resolve(
-    State(pool): State<DbPool>,
+    State(_pool): State<DbPool>,
     _auth_user: AuthUser,
     axum::extract::Query(query): axum::extract::Query<ResolveParams>,
 ) -> Result<Json<ResolveDeliveryServiceOutput<'static>>, FederationError> {
@@ -24,18 +24,12 @@ pub async fn resolve(
     let self_endpoint =
         std::env::var("SELF_ENDPOINT").unwrap_or_else(|_| "https://mls.catbird.blue".to_string());
     let default_ds = std::env::var("DEFAULT_DS_ENDPOINT").ok();
-    let cache_ttl: u64 = std::env::var("ENDPOINT_CACHE_TTL")
-        .ok()
-        .and_then(|v| v.parse().ok())
-        .unwrap_or(3600);
 
-    let resolver = DsResolver::new(
-        pool.clone(),
+    let resolver = RemoteDsResolver::new(
         reqwest::Client::new(),
         self_did,
         self_endpoint,
         default_ds,
-        cache_ttl,
     );
 
     let ds_endpoint = resolver.resolve(user_did).await?;
@@ -1,12 +1,20 @@
 use axum::{extract::State, http::StatusCode, Json};
+use sqlx::FromRow;
 use tracing::{info, error};
 
 use crate::{
     auth::{AuthUser, verify_is_admin, enforce_standard},
     generated::blue::catbird::mls::resolve_report::{Input, Output, OutputData, NSID},
+    query::SelectBuilder,
     storage::DbPool,
 };
 
+#[derive(FromRow)]
+struct ReportLookupRow {
+    convo_id: String,
+    status: String,
+}
+
 /// Resolve a report with an action (admin-only)
 /// POST /xrpc/blue.catbird.mls.resolveReport
 #[tracing::instrument(skip(pool, auth_user))]
@@ -42,20 +50,19 @@ pub async fn resolve_report(
     }
 
     // Fetch report to get convo_id and verify it exists
-    let (convo_id, current_status): (String, String) = sqlx::query_as(
-        "SELECT convo_id, status FROM reports WHERE id = $1"
-    )
-    .bind(&input.report_id)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|e| {
-        error!("❌ [resolve_report] Database query failed: {}", e);
-        StatusCode::INTERNAL_SERVER_ERROR
-    })?
-    .ok_or_else(|| {
-        error!("❌ [resolve_report] Report not found");
-        StatusCode::NOT_FOUND
-    })?;
+    let ReportLookupRow { convo_id, status: current_status } =
+        SelectBuilder::new("convo_id, status", "reports")
+            .filter("id", input.report_id.clone())
+            .fetch_optional(&pool)
+            .await
+            .map_err(|e| {
+                error!("❌ [resolve_report] Database query failed: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            })?
+            .ok_or_else(|| {
+                error!("❌ [resolve_report] Report not found");
+                StatusCode::NOT_FOUND
+            })?;
 
     // Verify admin status for this conversation
     verify_is_admin(&pool, &convo_id, &auth_user.did).await?;
@@ -93,6 +100,7 @@ pub async fn resolve_report(
         return Err(StatusCode::INTERNAL_SERVER_ERROR);
     }
 
+    crate::metrics::record_report_resolved(&input.action);
     info!("✅ [resolve_report] SUCCESS - report {} resolved with action '{}'",
           input.report_id, input.action);
 
@@ -3,7 +3,9 @@ use std::sync::Arc;
 use tracing::{error, info};
 
 use crate::{
+    actors::{ActorRegistry, ConvoEvent, ConvoMessage},
     auth::AuthUser,
+    federation::{RemoteEphemeralEvent, RemoteNodeRegistry},
     generated::blue::catbird::mls::send_typing_indicator::{Input, Output, NSID},
     realtime::{SseState, StreamEvent},
     db,
@@ -12,10 +14,12 @@ use crate::{
 
 /// Send a typing indicator to a conversation
 /// POST /xrpc/blue.catbird.mls.sendTypingIndicator
-#[tracing::instrument(skip(pool, sse_state, auth_user))]
+#[tracing::instrument(skip(pool, sse_state, actor_registry, remote_node_registry, auth_user))]
 pub async fn send_typing_indicator(
     State(pool): State<DbPool>,
     State(sse_state): State<Arc<SseState>>,
+    State(actor_registry): State<Arc<ActorRegistry>>,
+    State(remote_node_registry): State<Option<Arc<RemoteNodeRegistry>>>,
     auth_user: AuthUser,
     Json(input): Json<Input>,
 ) -> Result<Json<Output>, StatusCode> {
@@ -62,6 +66,34 @@ pub async fn send_typing_indicator(
         // Don't fail the request - typing indicators are best-effort
     }
 
+    // Also publish to subscribeConvo long-poll waiters instead of dropping
+    // the event on the floor for clients not using SSE.
+    match actor_registry.get_or_spawn(&input.convo_id).await {
+        Ok(actor_ref) => {
+            let _ = actor_ref.cast(ConvoMessage::Notify(ConvoEvent::Typing {
+                did: user_did.clone(),
+                is_typing: input.is_typing,
+            }));
+        }
+        Err(e) => error!("Failed to get conversation actor for notify: {}", e),
+    }
+
+    // Forward to members hosted on a different delivery service. Best-effort:
+    // failures here never affect the local success response.
+    if let Some(registry) = remote_node_registry {
+        if let Ok(members) = db::list_members(&pool, &input.convo_id).await {
+            registry.broadcast(
+                input.convo_id.clone(),
+                members.into_iter().map(|m| m.member_did).collect(),
+                RemoteEphemeralEvent::Typing {
+                    did: user_did.clone(),
+                    is_typing: input.is_typing,
+                },
+            );
+        }
+    }
+
+    crate::metrics::record_typing_indicator();
     info!("Typing indicator sent successfully");
 
     Ok(Json(Output { success: true }))
@@ -0,0 +1,102 @@
+use axum::{
+    extract::{Query, State},
+    http::StatusCode,
+    Json,
+};
+use serde::Deserialize;
+use std::{sync::Arc, time::Duration};
+use tokio::sync::oneshot;
+use tracing::{error, info, warn};
+
+use crate::{
+    actors::{ActorRegistry, ConvoEvent, ConvoMessage},
+    auth::AuthUser,
+    storage::DbPool,
+};
+
+/// How long to block waiting for an event before returning an empty batch.
+const SUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Deserialize)]
+pub struct SubscribeConvoQuery {
+    #[serde(rename = "convoId")]
+    pub convo_id: String,
+}
+
+/// IMAP-IDLE-style long-poll for new messages, reactions, and typing indicators.
+/// GET /xrpc/blue.catbird.mls.subscribeConvo
+///
+/// After verifying membership, registers a broadcast waiter with the
+/// conversation's actor and blocks for up to [`SUBSCRIBE_TIMEOUT`]. On wake it
+/// returns every event that arrived (draining any further ones already
+/// queued) along with the new `lastSeq`; on timeout it returns an empty
+/// batch so the client can immediately re-subscribe.
+#[tracing::instrument(skip(pool, actor_registry, auth_user))]
+pub async fn subscribe_convo(
+    State(pool): State<DbPool>,
+    State(actor_registry): State<Arc<ActorRegistry>>,
+    auth_user: AuthUser,
+    Query(query): Query<SubscribeConvoQuery>,
+) -> Result<Json<serde_json::Value>, StatusCode> {
+    let is_member = crate::db::is_member(&pool, &auth_user.did, &query.convo_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to check membership: {:?}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    if !is_member {
+        warn!("User is not a member of the conversation");
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    let actor_ref = actor_registry
+        .get_or_spawn(&query.convo_id)
+        .await
+        .map_err(|e| {
+            error!("Failed to get conversation actor: {}", e);
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let (tx, rx) = oneshot::channel();
+    actor_ref
+        .send_message(ConvoMessage::Subscribe { reply: tx })
+        .map_err(|_| {
+            error!("Failed to register subscriber with actor");
+            StatusCode::INTERNAL_SERVER_ERROR
+        })?;
+
+    let mut receiver = rx.await.map_err(|_| {
+        error!("Actor channel closed unexpectedly");
+        StatusCode::INTERNAL_SERVER_ERROR
+    })?;
+
+    let mut events = Vec::new();
+    let mut last_seq: Option<i64> = None;
+
+    // Block for the first event, then drain anything else already queued
+    // without waiting further - the goal is "return promptly once there's
+    // something to return", not "wait the full timeout regardless".
+    if let Ok(Ok(event)) = tokio::time::timeout(SUBSCRIBE_TIMEOUT, receiver.recv()).await {
+        if let ConvoEvent::Message { seq, .. } = &event {
+            last_seq = Some(*seq);
+        }
+        events.push(event);
+
+        while let Ok(event) = receiver.try_recv() {
+            if let ConvoEvent::Message { seq, .. } = &event {
+                last_seq = Some(*seq);
+            }
+            events.push(event);
+        }
+    }
+
+    info!("subscribeConvo returning {} event(s)", events.len());
+
+    let mut response = serde_json::json!({ "events": events });
+    if let Some(seq) = last_seq {
+        response["lastSeq"] = serde_json::json!(seq);
+    }
+
+    Ok(Json(response))
+}
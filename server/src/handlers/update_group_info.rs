@@ -10,6 +10,7 @@ use crate::{
     auth::AuthUser,
     error_responses::UpdateGroupInfoError,
     generated::blue::catbird::mls::update_group_info::{Input, Output, OutputData, Error},
+    query::SelectBuilder,
     storage::DbPool,
     group_info::{store_group_info, get_group_info, MIN_GROUP_INFO_SIZE, MAX_GROUP_INFO_SIZE},
 };
@@ -28,17 +29,13 @@ pub async fn handle(
     let did = &auth.did;
     
     // 1. Check authorization: must be current member
-    let member_check: Option<MemberCheckRow> = sqlx::query_as(
-        "SELECT member_did 
-         FROM members 
-         WHERE convo_id = $1 AND user_did = $2
-         LIMIT 1"
-    )
-    .bind(&input.data.convo_id)
-    .bind(did)
-    .fetch_optional(&pool)
-    .await
-    .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    let member_check: Option<MemberCheckRow> = SelectBuilder::new("member_did", "members")
+        .filter("convo_id", input.data.convo_id.clone())
+        .filter("user_did", did.clone())
+        .limit(1)
+        .fetch_optional(&pool)
+        .await
+        .map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
     
     if member_check.is_none() {
         return Err(Error::Unauthorized(
@@ -55,6 +52,7 @@ pub async fn handle(
                 error = %e,
                 "Invalid base64 in GroupInfo"
             );
+            crate::metrics::record_group_info_update_rejected("invalid_base64");
             Error::InvalidGroupInfo(Some("Invalid base64 encoding".into()))
         })?;
 
@@ -66,6 +64,7 @@ pub async fn handle(
             min_size = MIN_GROUP_INFO_SIZE,
             "GroupInfo too small - likely truncated"
         );
+        crate::metrics::record_group_info_update_rejected("size_too_small");
         return Err(Error::InvalidGroupInfo(Some(format!(
             "GroupInfo too small: {} bytes (minimum {} required)",
             group_info_bytes.len(), MIN_GROUP_INFO_SIZE
@@ -79,6 +78,7 @@ pub async fn handle(
             max_size = MAX_GROUP_INFO_SIZE,
             "GroupInfo too large"
         );
+        crate::metrics::record_group_info_update_rejected("size_too_large");
         return Err(Error::InvalidGroupInfo(Some(format!(
             "GroupInfo too large: {} bytes (maximum {} allowed)",
             group_info_bytes.len(), MAX_GROUP_INFO_SIZE
@@ -97,6 +97,7 @@ pub async fn handle(
             size = group_info_bytes.len(),
             "Invalid MLS GroupInfo structure - deserialization failed for both wrapped and raw formats"
         );
+        crate::metrics::record_group_info_update_rejected("invalid_structure");
         return Err(Error::InvalidGroupInfo(Some(
             "Invalid MLS GroupInfo structure: could not deserialize as MlsMessage or raw GroupInfo".into()
         )).into());
@@ -111,6 +112,7 @@ pub async fn handle(
                 existing_epoch = existing_epoch,
                 "Rejecting GroupInfo with non-increasing epoch"
             );
+            crate::metrics::record_group_info_update_rejected("epoch_not_increasing");
             return Err(Error::InvalidGroupInfo(Some(format!(
                 "Epoch {} must be greater than current epoch {}",
                 input.data.epoch, existing_epoch
@@ -139,7 +141,9 @@ pub async fn handle(
         );
         StatusCode::INTERNAL_SERVER_ERROR
     })?;
-    
+
+    crate::metrics::record_group_info_update_accepted();
+
     Ok(Json(Output {
         data: OutputData {
             updated: true,
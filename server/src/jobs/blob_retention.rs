@@ -0,0 +1,118 @@
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::time::interval;
+use tracing::{error, info};
+
+use crate::blob_storage::BlobStorage;
+use crate::db::DbPool;
+
+/// Background worker that reclaims R2 storage for delivered and expired
+/// message blobs left behind by `handlers::messages::store_message`.
+///
+/// Blobs are always deleted from R2 before their row is removed from
+/// Postgres. A crash between the two leaves the row in place rather than an
+/// orphaned blob: the next sweep re-finds the same row, retries the R2
+/// delete (a no-op against an already-missing key), and finishes the
+/// cleanup - so storage is reclaimed eventually without ever needing a
+/// separate orphan-blob scan.
+pub async fn run_blob_retention_worker(
+    pool: DbPool,
+    blob_storage: Arc<BlobStorage>,
+    sweep_interval: Duration,
+    delivered_grace: chrono::Duration,
+) {
+    let mut ticker = interval(sweep_interval);
+
+    info!(
+        interval_secs = sweep_interval.as_secs(),
+        grace_secs = delivered_grace.num_seconds(),
+        "Starting blob retention worker"
+    );
+
+    loop {
+        ticker.tick().await;
+
+        match sweep_expired(&pool, &blob_storage).await {
+            Ok(count) if count > 0 => info!(count, "Reclaimed expired message blobs"),
+            Ok(_) => {}
+            Err(e) => error!(error = %e, "Expired blob sweep failed"),
+        }
+
+        match sweep_delivered(&pool, &blob_storage, delivered_grace).await {
+            Ok(count) if count > 0 => info!(count, "Reclaimed fully-delivered message blobs"),
+            Ok(_) => {}
+            Err(e) => error!(error = %e, "Delivered blob sweep failed"),
+        }
+    }
+}
+
+/// Delete blobs (and rows) whose `expires_at` has passed.
+async fn sweep_expired(pool: &DbPool, blob_storage: &BlobStorage) -> anyhow::Result<u64> {
+    let message_ids: Vec<String> = sqlx::query_scalar(
+        "SELECT id FROM messages WHERE expires_at IS NOT NULL AND expires_at < NOW()",
+    )
+    .fetch_all(pool)
+    .await?;
+
+    for message_id in &message_ids {
+        delete_message_and_blob(pool, blob_storage, message_id).await?;
+    }
+
+    Ok(message_ids.len() as u64)
+}
+
+/// Delete blobs (and rows) for messages where every recipient has already
+/// fetched it (`message_recipients.delivered = true` for all rows) and the
+/// message is older than `grace` - long enough that a client which is about
+/// to come back online still gets a chance to re-fetch via `sync_messages`
+/// before the row disappears.
+async fn sweep_delivered(
+    pool: &DbPool,
+    blob_storage: &BlobStorage,
+    grace: chrono::Duration,
+) -> anyhow::Result<u64> {
+    let cutoff = chrono::Utc::now() - grace;
+
+    let message_ids: Vec<String> = sqlx::query_scalar(
+        r#"
+        SELECT m.id
+        FROM messages m
+        WHERE m.created_at < $1
+          AND EXISTS (SELECT 1 FROM message_recipients mr WHERE mr.message_id = m.id)
+          AND NOT EXISTS (
+              SELECT 1 FROM message_recipients mr
+              WHERE mr.message_id = m.id AND mr.delivered = false
+          )
+        "#,
+    )
+    .bind(cutoff)
+    .fetch_all(pool)
+    .await?;
+
+    for message_id in &message_ids {
+        delete_message_and_blob(pool, blob_storage, message_id).await?;
+    }
+
+    Ok(message_ids.len() as u64)
+}
+
+async fn delete_message_and_blob(
+    pool: &DbPool,
+    blob_storage: &BlobStorage,
+    message_id: &str,
+) -> anyhow::Result<()> {
+    blob_storage.delete_blob(message_id).await?;
+
+    let mut tx = pool.begin().await?;
+    sqlx::query("DELETE FROM message_recipients WHERE message_id = $1")
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+    sqlx::query("DELETE FROM messages WHERE id = $1")
+        .bind(message_id)
+        .execute(&mut *tx)
+        .await?;
+    tx.commit().await?;
+
+    Ok(())
+}
@@ -1,7 +1,9 @@
+pub mod blob_retention;
 pub mod compact_cursors;
 pub mod data_compaction;
 pub mod key_package_cleanup;
 
+pub use blob_retention::run_blob_retention_worker;
 pub use compact_cursors::{run_compaction_worker, CompactionConfig};
 pub use data_compaction::run_compaction_worker as run_data_compaction_worker;
 pub use key_package_cleanup::run_key_package_cleanup_worker;
@@ -1,4 +1,5 @@
 pub mod auth;
+pub mod blob_storage;
 pub mod crypto;
 pub mod db;
 pub mod fanout;
@@ -8,6 +9,8 @@ pub mod jobs;
 pub mod metrics;
 pub mod middleware;
 pub mod models;
+pub mod push;
+pub mod query;
 pub mod realtime;
 pub mod storage;
 pub mod util;
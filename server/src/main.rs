@@ -1,3 +1,4 @@
+use anyhow::Context;
 use axum::{
     extract::FromRef,
     routing::{any, get, post},
@@ -28,6 +29,27 @@ struct AppState {
     sse_state: Arc<realtime::SseState>,
     actor_registry: Arc<actors::ActorRegistry>,
     notification_service: Option<Arc<catbird_server::notifications::NotificationService>>,
+    remote_node_registry: Option<Arc<catbird_server::federation::RemoteNodeRegistry>>,
+    /// Shared resolver for verifying a forwarding DS is actually the home
+    /// node of an event's actor (`handlers::ds::ingest_remote_event`) - one
+    /// client reused across requests rather than built per-request.
+    ds_resolver: Arc<dyn catbird_server::federation::DsResolver>,
+}
+
+/// State for the REST-style `/api/v1/*` message endpoints
+/// (`handlers::messages`) and their federated-delivery counterpart
+/// (`handlers::ds::deliver_blob_message`). Kept separate from [`AppState`]
+/// rather than folded in, the same way `metrics_router`/`admin_router` carry
+/// their own state below - these handlers are the only ones that need blob
+/// storage and the outbound federation queue.
+#[derive(Clone, FromRef)]
+struct MessagingState {
+    db_pool: PgPool,
+    blob_storage: Arc<catbird_server::blob_storage::BlobStorage>,
+    push_fanout: Option<Arc<catbird_server::push::PushFanout>>,
+    federation_config: catbird_server::federation::FederationConfig,
+    ds_resolver: Arc<dyn catbird_server::federation::DsResolver>,
+    outbound_queue: Arc<catbird_server::federation::queue::OutboundQueue>,
 }
 
 #[tokio::main]
@@ -99,6 +121,86 @@ async fn main() -> anyhow::Result<()> {
     let notification_service = Some(Arc::new(catbird_server::notifications::NotificationService::new()));
     tracing::info!("Notification service initialized");
 
+    // DS endpoint resolution and the outbound delivery queue are needed
+    // regardless of whether reaction/typing fan-out (`remote_node_registry`,
+    // below) is turned on - `handlers::messages::store_message` always needs
+    // to know whether a recipient is local or federated.
+    let federation_config = catbird_server::federation::FederationConfig::from_env();
+    let remote_resolver = catbird_server::federation::RemoteDsResolver::new(
+        reqwest::Client::new(),
+        federation_config.self_did.clone(),
+        federation_config.self_endpoint.clone(),
+        federation_config.default_ds_endpoint.clone(),
+    );
+    let ds_resolver: Arc<dyn catbird_server::federation::DsResolver> =
+        Arc::new(catbird_server::federation::CachedDsResolver::new(
+            Arc::new(remote_resolver),
+            federation_config.endpoint_cache_ttl_secs,
+            federation_config.endpoint_negative_cache_ttl_secs,
+        ));
+    let outbound_client = catbird_server::federation::outbound::OutboundClient::new(
+        federation_config.outbound_connect_timeout_secs,
+        federation_config.outbound_timeout_secs,
+    );
+    let request_signer = federation_config
+        .signing_key_pem
+        .as_ref()
+        .and_then(|pem| {
+            catbird_server::federation::RequestSigner::from_es256_pem(
+                pem.as_bytes(),
+                federation_config.self_did.clone(),
+            )
+            .inspect_err(|e| tracing::error!("Invalid federation signing key: {}", e))
+            .ok()
+        })
+        .map(Arc::new);
+    let outbound_queue = Arc::new(catbird_server::federation::queue::OutboundQueue::new(
+        db_pool.clone(),
+        auth::AuthMiddleware::new(),
+        request_signer,
+    ));
+
+    // Initialize federation reaction/typing fan-out, only when federation is configured
+    let remote_node_registry = if federation_config.enabled {
+        let auth = federation_config
+            .signing_key_pem
+            .as_ref()
+            .and_then(|pem| {
+                catbird_server::federation::ServiceAuthClient::from_es256_pem(
+                    federation_config.self_did.clone(),
+                    pem.as_bytes(),
+                    None,
+                )
+                .inspect_err(|e| tracing::error!("Invalid federation signing key: {}", e))
+                .ok()
+            })
+            .map(Arc::new);
+        tracing::info!("Remote node registry initialized (federation enabled)");
+        Some(Arc::new(catbird_server::federation::RemoteNodeRegistry::new(
+            ds_resolver.clone(),
+            outbound_client,
+            auth,
+        )))
+    } else {
+        tracing::info!("Federation disabled, ephemeral events stay local");
+        None
+    };
+
+    // Blob storage backs the REST-style `/api/v1/messages*` endpoints
+    // (`handlers::messages`) and their federated-delivery counterpart
+    // (`handlers::ds::deliver_blob_message`). Push fan-out is best-effort and
+    // stays `None` until a provider is configured, same as
+    // `notification_service`/`remote_node_registry` above.
+    let blob_storage = Arc::new(
+        catbird_server::blob_storage::BlobStorage::new(
+            catbird_server::blob_storage::BlobStorageConfig::default(),
+        )
+        .await
+        .context("Failed to initialize blob storage")?,
+    );
+    let push_fanout: Option<Arc<catbird_server::push::PushFanout>> = None;
+    tracing::info!("Blob storage initialized");
+
     // Spawn idempotency cache cleanup worker
     let cleanup_pool = db_pool.clone();
     tokio::spawn(async move {
@@ -128,6 +230,19 @@ async fn main() -> anyhow::Result<()> {
     });
     tracing::info!("Key package cleanup worker started");
 
+    // Spawn sequencer-change listener so failovers committed by other app
+    // instances (or other processes) against the same database invalidate
+    // this instance's in-memory sequencer/epoch cache instead of it only
+    // learning about them on its next unrelated query.
+    let sequencer_cache = Arc::new(catbird_server::federation::SequencerCache::new());
+    let sequencer_listener_pool = db_pool.clone();
+    tokio::spawn(catbird_server::federation::notify::run_listener(
+        sequencer_listener_pool,
+        sequencer_cache,
+        tokio_util::sync::CancellationToken::new(),
+    ));
+    tracing::info!("Sequencer-change listener started");
+
     // Spawn rate limiter cleanup worker (clean up stale buckets every 5 minutes)
     tokio::spawn(async move {
         let mut interval_timer = interval(Duration::from_secs(300)); // Every 5 minutes
@@ -141,12 +256,24 @@ async fn main() -> anyhow::Result<()> {
     });
     tracing::info!("Rate limiter cleanup worker started");
 
+    // State for the REST-style `/api/v1/*` message endpoints
+    let messaging_state = MessagingState {
+        db_pool: db_pool.clone(),
+        blob_storage,
+        push_fanout,
+        federation_config: federation_config.clone(),
+        ds_resolver: ds_resolver.clone(),
+        outbound_queue,
+    };
+
     // Create composite app state
     let app_state = AppState {
         db_pool: db_pool.clone(),
         sse_state,
         actor_registry,
         notification_service,
+        ds_resolver,
+        remote_node_registry,
     };
 
     // Build application router
@@ -286,6 +413,31 @@ async fn main() -> anyhow::Result<()> {
             "/xrpc/blue.catbird.mls.updateCursor",
             post(handlers::update_cursor),
         )
+        .route(
+            "/xrpc/blue.catbird.mls.addReaction",
+            post(handlers::add_reaction),
+        )
+        .route(
+            "/xrpc/blue.catbird.mls.removeReaction",
+            post(handlers::remove_reaction),
+        )
+        .route(
+            "/xrpc/blue.catbird.mls.sendTypingIndicator",
+            post(handlers::send_typing_indicator),
+        )
+        .route(
+            "/xrpc/blue.catbird.mls.subscribeConvo",
+            get(handlers::subscribe_convo),
+        )
+        .route(
+            "/xrpc/blue.catbird.mls.batch",
+            post(handlers::batch),
+        )
+        // DS-to-DS federation endpoints
+        .route(
+            "/xrpc/blue.catbird.mls.ds.ingestRemoteEvent",
+            post(handlers::ds::ingest_remote_event),
+        )
         .merge(metrics_router)
         .layer(TraceLayer::new_for_http())
         .layer(axum::middleware::from_fn(middleware::logging::log_headers_middleware))
@@ -325,6 +477,37 @@ async fn main() -> anyhow::Result<()> {
         )
         .with_state(app_state.clone());
 
+    // REST-style message endpoints (`handlers::messages`) - the mobile
+    // clients' blob store-and-forward path, separate from the XRPC
+    // `sendMessage`/`getMessages` pair above which carries MLS group
+    // operations rather than raw ciphertext blobs.
+    let messaging_router = Router::new()
+        .route(
+            "/api/v1/messages",
+            post(handlers::store_message),
+        )
+        .route(
+            "/api/v1/messages/sync",
+            get(handlers::sync_messages),
+        )
+        .route(
+            "/api/v1/messages/pending",
+            get(handlers::list_pending_messages),
+        )
+        .route(
+            "/api/v1/messages/:message_id",
+            get(handlers::get_message).delete(handlers::delete_message),
+        )
+        .route(
+            "/api/v1/devices/:device_id/push-token",
+            post(handlers::register_push_token),
+        )
+        .route(
+            "/xrpc/blue.catbird.mls.deliverMessage",
+            post(handlers::ds::deliver_blob_message),
+        )
+        .with_state(messaging_state);
+
     // ⚠️ SECURITY: Developer-only direct XRPC proxy - NEVER enable in production
     // This is gated with #[cfg(debug_assertions)] to prevent accidental production use
     #[cfg(debug_assertions)]
@@ -355,7 +538,7 @@ async fn main() -> anyhow::Result<()> {
         );
     }
 
-    let app = base_router.merge(admin_router);
+    let app = base_router.merge(admin_router).merge(messaging_router);
 
     let port = std::env::var("SERVER_PORT")
         .unwrap_or_else(|_| "8080".to_string())
@@ -80,6 +80,74 @@ impl MetricsRecorder {
             "Number of detected epoch conflicts"
         );
 
+        // DS throughput metrics
+        metrics::describe_counter!(
+            "ds_messages_fetched_total",
+            "Total number of messages returned by getMessages"
+        );
+        metrics::describe_histogram!(
+            "ds_get_messages_duration_seconds",
+            "Time spent serving getMessages requests"
+        );
+        metrics::describe_counter!(
+            "ds_message_gaps_detected_total",
+            "Total number of missing sequence numbers detected by detect_message_gaps"
+        );
+        metrics::describe_counter!(
+            "ds_reactions_total",
+            "Total reaction add/remove operations, by action"
+        );
+        metrics::describe_counter!(
+            "ds_typing_indicators_total",
+            "Total typing indicator events sent"
+        );
+
+        // GroupInfo metrics
+        metrics::describe_counter!(
+            "ds_group_info_updates_total",
+            "Total accepted GroupInfo updates"
+        );
+        metrics::describe_counter!(
+            "ds_group_info_rejections_total",
+            "Total rejected GroupInfo updates, by reason"
+        );
+
+        // Moderation metrics
+        metrics::describe_counter!(
+            "ds_report_submissions_total",
+            "Total member reports submitted"
+        );
+        metrics::describe_counter!(
+            "ds_report_resolutions_total",
+            "Total member reports resolved, by resolution action"
+        );
+        metrics::describe_counter!(
+            "ds_reports_queried_total",
+            "Total reports returned by getReports, by conversation status filter"
+        );
+
+        // Outbound federation queue metrics
+        metrics::describe_counter!(
+            "outbound_queue_enqueued_total",
+            "Total items enqueued for retried cross-DS delivery"
+        );
+        metrics::describe_counter!(
+            "outbound_queue_delivered_total",
+            "Total queued items successfully delivered"
+        );
+        metrics::describe_counter!(
+            "outbound_queue_slow_deliveries_total",
+            "Total deliveries (direct or queued) exceeding the slow-delivery threshold"
+        );
+        metrics::describe_counter!(
+            "outbound_queue_dead_lettered_total",
+            "Total items that exhausted their retry budget and were dead-lettered"
+        );
+        metrics::describe_histogram!(
+            "outbound_delivery_duration_seconds",
+            "Time spent on a single outbound federation delivery attempt"
+        );
+
         Self { handle }
     }
 
@@ -304,3 +372,116 @@ pub fn record_epoch_increment(_convo_id: &str, duration: Duration) {
 pub fn record_epoch_conflict(_convo_id: &str) {
     metrics::counter!("epoch_conflicts_total", 1);
 }
+
+// ============================================================================
+// DS Throughput Metrics
+// ============================================================================
+
+/// Record how many messages a single getMessages call returned.
+pub fn record_messages_fetched(count: usize) {
+    metrics::counter!("ds_messages_fetched_total", count as u64);
+}
+
+/// Record how long a getMessages call took to serve, selector included so
+/// operators can see whether BEFORE/AFTER/AROUND/BETWEEN paging is slower
+/// than the LATEST default.
+pub fn record_get_messages_duration(selector: &str, duration: Duration) {
+    metrics::histogram!(
+        "ds_get_messages_duration_seconds",
+        duration.as_secs_f64(),
+        "selector" => selector.to_string()
+    );
+}
+
+/// Record sequence gaps surfaced by `detect_message_gaps` for a getMessages
+/// response, so gap storms show up as a rate spike rather than only in logs.
+pub fn record_message_gaps(missing_count: usize) {
+    if missing_count > 0 {
+        metrics::counter!("ds_message_gaps_detected_total", missing_count as u64);
+    }
+}
+
+/// Record a reaction add/remove, whether from the standalone endpoints or
+/// the `batch` endpoint's coalesced version of the same operation.
+pub fn record_reaction(action: &str) {
+    metrics::counter!("ds_reactions_total", 1, "action" => action.to_string());
+}
+
+/// Record a typing indicator send.
+pub fn record_typing_indicator() {
+    metrics::counter!("ds_typing_indicators_total", 1);
+}
+
+// ============================================================================
+// GroupInfo Metrics
+// ============================================================================
+
+/// Record a successfully validated and stored GroupInfo update.
+pub fn record_group_info_update_accepted() {
+    metrics::counter!("ds_group_info_updates_total", 1);
+}
+
+/// Record a rejected GroupInfo update. `reason` should be one of
+/// `invalid_base64`, `size_too_small`, `size_too_large`, `invalid_structure`,
+/// or `epoch_not_increasing` so rejection spikes can be broken down by cause.
+pub fn record_group_info_update_rejected(reason: &str) {
+    metrics::counter!("ds_group_info_rejections_total", 1, "reason" => reason.to_string());
+}
+
+// ============================================================================
+// Moderation Metrics
+// ============================================================================
+
+/// Record a member report submission.
+pub fn record_report_submitted() {
+    metrics::counter!("ds_report_submissions_total", 1);
+}
+
+/// Record a report resolution, broken down by the action the admin took.
+pub fn record_report_resolved(action: &str) {
+    metrics::counter!("ds_report_resolutions_total", 1, "action" => action.to_string());
+}
+
+/// Record a getReports query, broken down by the status filter applied (or
+/// `"all"` when the caller didn't filter by status).
+pub fn record_reports_queried(count: usize, status_filter: &str) {
+    metrics::counter!(
+        "ds_reports_queried_total",
+        count as u64,
+        "status_filter" => status_filter.to_string()
+    );
+}
+
+// ============================================================================
+// Outbound Federation Queue Metrics
+// ============================================================================
+
+/// Record an item enqueued for retried cross-DS delivery, by XRPC method.
+pub fn record_outbound_enqueued(method: &str) {
+    metrics::counter!("outbound_queue_enqueued_total", 1, "method" => method.to_string());
+}
+
+/// Record a successful delivery, whether sent directly or drained from the
+/// retry queue.
+pub fn record_outbound_delivered(method: &str) {
+    metrics::counter!("outbound_queue_delivered_total", 1, "method" => method.to_string());
+}
+
+/// Record a delivery attempt's wall-clock duration, flagging it as slow once
+/// it crosses the configured threshold so operators can alert on the rate
+/// rather than wait for timeouts to show up as failures.
+pub fn record_outbound_delivery_duration(method: &str, duration: Duration, slow: bool) {
+    metrics::histogram!(
+        "outbound_delivery_duration_seconds",
+        duration.as_secs_f64(),
+        "method" => method.to_string()
+    );
+    if slow {
+        metrics::counter!("outbound_queue_slow_deliveries_total", 1, "method" => method.to_string());
+    }
+}
+
+/// Record an item that exhausted its retry budget and was dead-lettered.
+pub fn record_outbound_dead_lettered(method: &str) {
+    metrics::counter!("outbound_queue_dead_lettered_total", 1, "method" => method.to_string());
+}
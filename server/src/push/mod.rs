@@ -0,0 +1,415 @@
+//! Push-notification wake-up fan-out for the blob-storage message path
+//! ([`crate::handlers::messages::store_message`]).
+//!
+//! This is a separate concern from [`crate::notifications::NotificationService`],
+//! which targets the MLS/XRPC `sendMessage` path and always ships ciphertext in
+//! the APNs payload. Here, devices register a push token once (`provider` +
+//! `mode`) via [`register_push_token`], and every subsequent fan-out either sends
+//! a silent `{convo_id, message_id}` data push - the default, since the DS never
+//! holds plaintext it can safely leave out of the payload - or, for devices opted
+//! into [`PushMode::Raw`] (mirroring WalletConnect's push-server `always_raw`
+//! flag), additionally includes the base64 ciphertext for a client that decrypts
+//! inside a notification-service extension.
+//!
+//! Delivery to each device is independent: [`PushFanout::notify`] spawns one
+//! background task per device with its own retry/backoff, so a single dead
+//! token can't stall (or fail) delivery to the rest of the recipients.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::warn;
+
+use crate::db::DbPool;
+
+/// Delivery mode selected per device at registration time.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PushMode {
+    /// Silent `{convo_id, message_id}` data push - the client fetches and
+    /// decrypts the blob itself. This is the default: the DS never holds
+    /// plaintext, and this mode never puts ciphertext in a provider payload.
+    Encrypted,
+    /// Same payload plus the base64 ciphertext, for clients that decrypt in a
+    /// notification-service extension.
+    Raw,
+}
+
+impl Default for PushMode {
+    fn default() -> Self {
+        Self::Encrypted
+    }
+}
+
+/// Push provider a device token is registered with.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PushProviderKind {
+    Fcm,
+    Apns,
+}
+
+impl PushProviderKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Fcm => "fcm",
+            Self::Apns => "apns",
+        }
+    }
+}
+
+/// A device's registered push token, as stored in `device_push_tokens`.
+#[derive(Debug, Clone, FromRow)]
+pub struct PushTokenRow {
+    pub device_id: String,
+    pub user_did: String,
+    pub provider: String,
+    pub token: String,
+    pub mode: String,
+}
+
+/// Wake-up payload delivered to a device.
+#[derive(Debug, Clone)]
+pub struct PushPayload {
+    pub convo_id: String,
+    pub message_id: String,
+    /// Present only when the device is registered for [`PushMode::Raw`].
+    pub ciphertext_b64: Option<String>,
+}
+
+/// Error returned by a [`PushProvider`]. Distinguishes transient failures
+/// worth retrying from a provider telling us the token is gone for good -
+/// worth deactivating so it isn't tried again on every future fan-out.
+#[derive(Debug, thiserror::Error)]
+pub enum PushError {
+    #[error("transient push delivery failure: {0}")]
+    Transient(#[source] anyhow::Error),
+
+    #[error("push token no longer valid: {0}")]
+    InvalidToken(#[source] anyhow::Error),
+}
+
+impl PushError {
+    fn is_retryable(&self) -> bool {
+        matches!(self, Self::Transient(_))
+    }
+}
+
+/// Pluggable push delivery backend.
+#[async_trait]
+pub trait PushProvider: Send + Sync {
+    async fn send(&self, token: &str, payload: &PushPayload) -> Result<(), PushError>;
+
+    fn kind(&self) -> PushProviderKind;
+}
+
+/// FCM (Firebase Cloud Messaging) HTTP v1 provider.
+pub struct FcmProvider {
+    http: reqwest::Client,
+    project_id: String,
+    access_token: String,
+}
+
+impl FcmProvider {
+    pub fn new(project_id: String, access_token: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            project_id,
+            access_token,
+        }
+    }
+}
+
+#[async_trait]
+impl PushProvider for FcmProvider {
+    async fn send(&self, token: &str, payload: &PushPayload) -> Result<(), PushError> {
+        let mut data = serde_json::json!({
+            "convo_id": payload.convo_id,
+            "message_id": payload.message_id,
+        });
+        if let Some(ciphertext_b64) = &payload.ciphertext_b64 {
+            data["ciphertext"] = serde_json::Value::String(ciphertext_b64.clone());
+        }
+
+        let url = format!(
+            "https://fcm.googleapis.com/v1/projects/{}/messages:send",
+            self.project_id
+        );
+
+        let response = self
+            .http
+            .post(&url)
+            .bearer_auth(&self.access_token)
+            .json(&serde_json::json!({
+                "message": {
+                    "token": token,
+                    "data": data,
+                    "android": { "priority": "high" },
+                }
+            }))
+            .send()
+            .await
+            .map_err(|e| PushError::Transient(e.into()))?;
+
+        let status = response.status();
+        if status.is_success() {
+            return Ok(());
+        }
+
+        let body = response.text().await.unwrap_or_default();
+        if status == reqwest::StatusCode::NOT_FOUND || body.contains("UNREGISTERED") {
+            return Err(PushError::InvalidToken(anyhow::anyhow!(
+                "FCM rejected token: {}",
+                body
+            )));
+        }
+
+        Err(PushError::Transient(anyhow::anyhow!(
+            "FCM returned {}: {}",
+            status,
+            body
+        )))
+    }
+
+    fn kind(&self) -> PushProviderKind {
+        PushProviderKind::Fcm
+    }
+}
+
+/// APNs data-only (background) push provider, used for the push-token
+/// fan-out registered through this module. [`crate::notifications`] owns a
+/// separate APNs client for the XRPC `sendMessage` path.
+pub struct ApnsProvider {
+    client: a2::Client,
+    topic: String,
+}
+
+impl ApnsProvider {
+    pub fn new(client: a2::Client, topic: String) -> Self {
+        Self { client, topic }
+    }
+}
+
+#[async_trait]
+impl PushProvider for ApnsProvider {
+    async fn send(&self, token: &str, payload: &PushPayload) -> Result<(), PushError> {
+        use a2::{DefaultNotificationBuilder, NotificationBuilder, NotificationOptions, Priority, PushType};
+
+        let mut notification = DefaultNotificationBuilder::new()
+            .set_content_available()
+            .build(
+                token,
+                NotificationOptions {
+                    apns_topic: Some(&self.topic),
+                    apns_priority: Some(Priority::High),
+                    apns_collapse_id: None,
+                    apns_expiration: None,
+                    apns_push_type: Some(PushType::Background),
+                    apns_id: None,
+                },
+            );
+
+        notification
+            .add_custom_data("convo_id", &payload.convo_id)
+            .map_err(|e| PushError::Transient(e.into()))?;
+        notification
+            .add_custom_data("message_id", &payload.message_id)
+            .map_err(|e| PushError::Transient(e.into()))?;
+        if let Some(ciphertext_b64) = &payload.ciphertext_b64 {
+            notification
+                .add_custom_data("ciphertext", ciphertext_b64)
+                .map_err(|e| PushError::Transient(e.into()))?;
+        }
+
+        match self.client.send(notification).await {
+            Ok(response) if response.code == 410 => Err(PushError::InvalidToken(
+                anyhow::anyhow!("APNs reports token unregistered (410)"),
+            )),
+            Ok(_) => Ok(()),
+            Err(e) => Err(PushError::Transient(e.into())),
+        }
+    }
+
+    fn kind(&self) -> PushProviderKind {
+        PushProviderKind::Apns
+    }
+}
+
+/// How many times to attempt delivery to a single device before giving up.
+const MAX_DELIVERY_ATTEMPTS: u32 = 3;
+
+/// Exponential backoff between delivery attempts to the same device: 1s, 2s, 4s.
+fn retry_backoff(attempt: u32) -> Duration {
+    Duration::from_secs(1u64.saturating_shl(attempt))
+}
+
+/// Registers [`PushProvider`] implementations and fans a wake-up push out to
+/// every device registered for a set of recipients.
+pub struct PushFanout {
+    providers: Vec<Arc<dyn PushProvider>>,
+}
+
+impl PushFanout {
+    pub fn new(providers: Vec<Arc<dyn PushProvider>>) -> Self {
+        Self { providers }
+    }
+
+    fn provider_for(&self, name: &str) -> Option<&Arc<dyn PushProvider>> {
+        self.providers.iter().find(|p| p.kind().as_str() == name)
+    }
+
+    /// Look up every device registered for `recipient_dids` and fan the
+    /// wake-up push out to each, one background task per device. Returns
+    /// immediately - callers (`store_message`) should not block the request
+    /// path on push delivery.
+    pub fn notify(
+        self: &Arc<Self>,
+        pool: DbPool,
+        convo_id: String,
+        message_id: String,
+        recipient_dids: Vec<String>,
+        ciphertext: Option<Vec<u8>>,
+    ) {
+        let fanout = Arc::clone(self);
+        tokio::spawn(async move {
+            let tokens: Vec<PushTokenRow> = match sqlx::query_as(
+                "SELECT device_id, user_did, provider, token, mode
+                 FROM device_push_tokens
+                 WHERE user_did = ANY($1)",
+            )
+            .bind(&recipient_dids)
+            .fetch_all(&pool)
+            .await
+            {
+                Ok(rows) => rows,
+                Err(e) => {
+                    warn!(convo_id, error = %e, "Failed to load push tokens for fan-out");
+                    return;
+                }
+            };
+
+            for row in tokens {
+                let Some(provider) = fanout.provider_for(&row.provider) else {
+                    warn!(device_id = %row.device_id, provider = %row.provider, "No push provider registered for device");
+                    continue;
+                };
+                let provider = Arc::clone(provider);
+                let pool = pool.clone();
+                let convo_id = convo_id.clone();
+                let message_id = message_id.clone();
+                let ciphertext_b64 = match row.mode.as_str() {
+                    "raw" => ciphertext
+                        .as_ref()
+                        .map(|bytes| base64::Engine::encode(&base64::engine::general_purpose::STANDARD, bytes)),
+                    _ => None,
+                };
+                let token = row.token.clone();
+                let device_id = row.device_id.clone();
+
+                tokio::spawn(async move {
+                    let payload = PushPayload {
+                        convo_id,
+                        message_id,
+                        ciphertext_b64,
+                    };
+                    deliver_with_retry(provider.as_ref(), &pool, &device_id, &token, &payload).await;
+                });
+            }
+        });
+    }
+}
+
+async fn deliver_with_retry(
+    provider: &dyn PushProvider,
+    pool: &DbPool,
+    device_id: &str,
+    token: &str,
+    payload: &PushPayload,
+) {
+    for attempt in 0..MAX_DELIVERY_ATTEMPTS {
+        if attempt > 0 {
+            tokio::time::sleep(retry_backoff(attempt - 1)).await;
+        }
+
+        match provider.send(token, payload).await {
+            Ok(()) => return,
+            Err(e) if e.is_retryable() => {
+                warn!(device_id, attempt, error = %e, "Push delivery attempt failed, retrying");
+            }
+            Err(e) => {
+                warn!(device_id, error = %e, "Push token is dead, deactivating");
+                deactivate_token(pool, device_id).await;
+                return;
+            }
+        }
+    }
+
+    warn!(device_id, "Giving up on push delivery after max retries");
+}
+
+async fn deactivate_token(pool: &DbPool, device_id: &str) {
+    if let Err(e) = sqlx::query("DELETE FROM device_push_tokens WHERE device_id = $1")
+        .bind(device_id)
+        .execute(pool)
+        .await
+    {
+        warn!(device_id, error = %e, "Failed to deactivate dead push token");
+    }
+}
+
+/// Upsert a device's push token registration.
+/// Backs `POST /api/v1/devices/:device_id/push-token`.
+pub async fn register_push_token(
+    pool: &DbPool,
+    device_id: &str,
+    user_did: &str,
+    provider: PushProviderKind,
+    token: &str,
+    mode: PushMode,
+) -> anyhow::Result<()> {
+    let mode_str = match mode {
+        PushMode::Encrypted => "encrypted",
+        PushMode::Raw => "raw",
+    };
+
+    sqlx::query(
+        "INSERT INTO device_push_tokens (device_id, user_did, provider, token, mode, updated_at)
+         VALUES ($1, $2, $3, $4, $5, now())
+         ON CONFLICT (device_id) DO UPDATE
+         SET provider = EXCLUDED.provider, token = EXCLUDED.token, mode = EXCLUDED.mode, updated_at = now()",
+    )
+    .bind(device_id)
+    .bind(user_did)
+    .bind(provider.as_str())
+    .bind(token)
+    .bind(mode_str)
+    .execute(pool)
+    .await?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_mode_is_encrypted() {
+        assert_eq!(PushMode::default(), PushMode::Encrypted);
+    }
+
+    #[test]
+    fn transient_errors_are_retryable() {
+        assert!(PushError::Transient(anyhow::anyhow!("timeout")).is_retryable());
+        assert!(!PushError::InvalidToken(anyhow::anyhow!("gone")).is_retryable());
+    }
+
+    #[test]
+    fn backoff_doubles_each_attempt() {
+        assert_eq!(retry_backoff(0), Duration::from_secs(1));
+        assert_eq!(retry_backoff(1), Duration::from_secs(2));
+        assert_eq!(retry_backoff(2), Duration::from_secs(4));
+    }
+}